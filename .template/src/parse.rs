@@ -1,7 +1,7 @@
+use advent::parsers::parse_all;
 use advent::prelude::*;
 
-pub fn parse_input(input: &str) -> Result<()> {
-    // let (_, digit) = digit1(input)?;
-
-    Ok(())
+/// Every line of the input, as a starting point to swap for the day's own grammar
+pub fn parse_input(input: &str) -> Result<Vec<&str>> {
+    parse_all(separated_list0(line_ending, not_line_ending), input)
 }
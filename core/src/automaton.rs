@@ -0,0 +1,418 @@
+use crate::coordinates::{GridDelta, GridStorage};
+use crate::prelude::*;
+
+/// Which cells a cell looks at when working out its next state
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// Only up, down, left and right
+    Four,
+    /// The four straight neighbours plus the diagonals
+    Eight,
+    /// Any set of offsets from the cell, in the order the rule will see them
+    Custom(Vec<GridDelta>),
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> Vec<GridDelta> {
+        match self {
+            Neighbourhood::Four => [
+                GridDirection::Up,
+                GridDirection::Down,
+                GridDirection::Left,
+                GridDirection::Right,
+            ]
+            .iter()
+            .map(GridDirection::as_vector)
+            .collect(),
+            Neighbourhood::Eight => GridDirection::directions()
+                .iter()
+                .map(GridDirection::as_vector)
+                .collect(),
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+}
+
+/// What happens at the edges of the starting grid
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Edges<T> {
+    /// Nothing exists outside the grid, so cells on the edge just have fewer neighbours
+    Bounded,
+    /// Going off one side comes back on the other, like a torus
+    Wrapping,
+    /// The grid carries on forever, filled with `background`
+    ///
+    /// Only cells that aren't background are stored, so this assumes a background cell that only
+    /// has background around it stays as it is.
+    Infinite { background: T },
+}
+
+/// The cells around a cell, lined up with the offsets of the `Neighbourhood`
+///
+/// A neighbour that's off the edge of a bounded grid is `None`.
+#[derive(Debug)]
+pub struct Neighbours<'a, T> {
+    cells: Vec<Option<&'a T>>,
+}
+
+impl<'a, T> Neighbours<'a, T> {
+    /// The neighbour at the offset with this index in the neighbourhood
+    pub fn get(&self, idx: usize) -> Option<&'a T> {
+        self.cells.get(idx).copied().flatten()
+    }
+
+    /// All the neighbours that exist, in the order of the neighbourhood
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.cells.iter().flatten().copied()
+    }
+
+    /// How many of the neighbours match the predicate
+    pub fn count<P>(&self, mut predicate: P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().filter(|cell| predicate(cell)).count()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Cells<T> {
+    Dense {
+        current: Grid<T>,
+        next: Grid<T>,
+        wrapping: bool,
+    },
+    Sparse {
+        current: HashMap<GridCoordinate<i64>, T>,
+        next: HashMap<GridCoordinate<i64>, T>,
+        background: T,
+    },
+}
+
+/// A grid of cells that all step forward together, each following the same local rule
+///
+/// Every generation is worked out from a complete copy of the last one, so the order cells are
+/// visited in never matters. The two copies are swapped rather than reallocated each step, and
+/// one buffer of neighbours is filled in for each cell in turn.
+#[derive(Debug, Clone)]
+pub struct Automaton<T> {
+    cells: Cells<T>,
+    offsets: Vec<GridDelta<i64>>,
+    generation: usize,
+    changed: usize,
+}
+
+impl<T: Clone + PartialEq> Automaton<T> {
+    pub fn new(grid: Grid<T>, neighbourhood: Neighbourhood, edges: Edges<T>) -> Self {
+        let cells = match edges {
+            Edges::Bounded | Edges::Wrapping => Cells::Dense {
+                next: grid.clone(),
+                current: grid,
+                wrapping: edges == Edges::Wrapping,
+            },
+            Edges::Infinite { background } => Cells::Sparse {
+                current: grid
+                    .iter()
+                    .filter(|(_, cell)| **cell != background)
+                    .map(|(coord, cell)| (coord.cast().unwrap(), cell.clone()))
+                    .collect(),
+                next: HashMap::new(),
+                background,
+            },
+        };
+
+        Self {
+            cells,
+            offsets: neighbourhood
+                .offsets()
+                .iter()
+                .map(|delta| GridDelta::new(delta.row as i64, delta.column as i64))
+                .collect(),
+            generation: 0,
+            changed: 0,
+        }
+    }
+
+    /// The number of steps taken so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The number of cells that changed in the last step
+    pub fn changed(&self) -> usize {
+        self.changed
+    }
+
+    /// The cell at a coordinate, which is the background anywhere in an infinite grid that hasn't
+    /// been filled in
+    pub fn get(&self, coord: GridCoordinate<i64>) -> Option<&T> {
+        match &self.cells {
+            Cells::Dense { current, .. } => current.get(&coord),
+            Cells::Sparse {
+                current,
+                background,
+                ..
+            } => Some(current.get(&coord).unwrap_or(background)),
+        }
+    }
+
+    /// How many cells match the predicate, not counting the endless background of an infinite
+    /// grid
+    pub fn count<P>(&self, mut predicate: P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        match &self.cells {
+            Cells::Dense { current, .. } => current.positions(predicate).count(),
+            Cells::Sparse { current, .. } => {
+                current.values().filter(|cell| predicate(cell)).count()
+            }
+        }
+    }
+
+    /// The cells as a grid, which for an infinite automaton is just big enough to hold everything
+    /// that isn't background, along with the coordinate of its top left corner
+    pub fn to_grid(&self) -> (Grid<T>, GridCoordinate<i64>) {
+        match &self.cells {
+            Cells::Dense { current, .. } => (current.clone(), GridCoordinate::new(0, 0)),
+            Cells::Sparse {
+                current,
+                background,
+                ..
+            } => {
+                let rows = current.keys().map(|coord| coord.row);
+                let cols = current.keys().map(|coord| coord.column);
+                let (Some(top), Some(left)) = (rows.clone().min(), cols.clone().min()) else {
+                    return (
+                        Grid::new(0, 0, background.clone()),
+                        GridCoordinate::new(0, 0),
+                    );
+                };
+                let height = (rows.max().unwrap_or(top) - top + 1) as usize;
+                let width = (cols.max().unwrap_or(left) - left + 1) as usize;
+
+                let mut grid = Grid::new(height, width, background.clone());
+                for (coord, cell) in current {
+                    grid[GridCoordinate::new(coord.row - top, coord.column - left)] = cell.clone();
+                }
+                (grid, GridCoordinate::new(top, left))
+            }
+        }
+    }
+
+    /// Move every cell on one generation, returning how many of them changed
+    ///
+    /// The rule gets the cell and its neighbours, and gives back what the cell becomes.
+    pub fn step<F>(&mut self, mut rule: F) -> usize
+    where
+        F: FnMut(&T, &Neighbours<T>) -> T,
+    {
+        let offsets = &self.offsets;
+        let changed = match &mut self.cells {
+            Cells::Dense {
+                current,
+                next,
+                wrapping,
+            } => {
+                let (height, width) = current.dimensions();
+                let (rows, cols) = (height as i64, width as i64);
+                let mut changed = 0;
+                let mut neighbours = Neighbours {
+                    cells: Vec::with_capacity(offsets.len()),
+                };
+
+                for (coord, cell) in current.iter() {
+                    neighbours.cells.clear();
+                    neighbours.cells.extend(offsets.iter().map(|delta| {
+                        let mut row = coord.row as i64 + delta.row;
+                        let mut col = coord.column as i64 + delta.column;
+                        if *wrapping {
+                            row = row.rem_euclid(rows);
+                            col = col.rem_euclid(cols);
+                        }
+                        current.get(&GridCoordinate::new(row, col))
+                    }));
+
+                    let new_cell = rule(cell, &neighbours);
+                    changed += usize::from(new_cell != *cell);
+                    *next.cell_mut(coord.row, coord.column) = new_cell;
+                }
+
+                std::mem::swap(current, next);
+                changed
+            }
+            Cells::Sparse {
+                current,
+                next,
+                background,
+            } => {
+                // Anything that could change is either already filled in or next to something that is
+                let candidates: HashSet<GridCoordinate<i64>> = current
+                    .keys()
+                    .flat_map(|coord| {
+                        std::iter::once(*coord).chain(offsets.iter().map(|delta| {
+                            GridCoordinate::new(coord.row - delta.row, coord.column - delta.column)
+                        }))
+                    })
+                    .collect();
+
+                let mut changed = 0;
+                let mut neighbours = Neighbours {
+                    cells: Vec::with_capacity(offsets.len()),
+                };
+                next.clear();
+                for coord in candidates {
+                    let cell = current.get(&coord).unwrap_or(background);
+                    neighbours.cells.clear();
+                    neighbours.cells.extend(offsets.iter().map(|delta| {
+                        let neighbour =
+                            GridCoordinate::new(coord.row + delta.row, coord.column + delta.column);
+                        Some(current.get(&neighbour).unwrap_or(background))
+                    }));
+
+                    let new_cell = rule(cell, &neighbours);
+                    changed += usize::from(new_cell != *cell);
+                    if new_cell != *background {
+                        next.insert(coord, new_cell);
+                    }
+                }
+
+                std::mem::swap(current, next);
+                changed
+            }
+        };
+
+        self.generation += 1;
+        self.changed = changed;
+        changed
+    }
+
+    /// Take a number of steps
+    pub fn run<F>(&mut self, generations: usize, mut rule: F)
+    where
+        F: FnMut(&T, &Neighbours<T>) -> T,
+    {
+        for _ in 0..generations {
+            self.step(&mut rule);
+        }
+    }
+
+    /// Keep stepping until a generation comes out the same as the one before it, returning the
+    /// number of steps that changed anything
+    ///
+    /// Gives up with an error after `limit` steps, as some rules never settle down.
+    pub fn run_until_stable<F>(&mut self, limit: usize, mut rule: F) -> Result<usize>
+    where
+        F: FnMut(&T, &Neighbours<T>) -> T,
+    {
+        for steps in 0..limit {
+            if self.step(&mut rule) == 0 {
+                return Ok(steps);
+            }
+        }
+        Err(error!("Still changing after {} generations", limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Conway's game of life, with `#` for a live cell
+    fn life(cell: &char, neighbours: &Neighbours<char>) -> char {
+        match (cell, neighbours.count(|n| *n == '#')) {
+            ('#', 2) | (_, 3) => '#',
+            _ => '.',
+        }
+    }
+
+    fn grid(s: &str) -> Grid<char> {
+        Grid::parse(s, Some).unwrap()
+    }
+
+    #[test]
+    fn test_blinker() {
+        let mut automaton = Automaton::new(
+            grid(".....\n..#..\n..#..\n..#..\n....."),
+            Neighbourhood::Eight,
+            Edges::Bounded,
+        );
+
+        assert_eq!(automaton.step(life), 4);
+        assert_eq!(
+            automaton.to_grid().0,
+            grid(".....\n.....\n.###.\n.....\n.....")
+        );
+        automaton.run(3, life);
+        assert_eq!(automaton.generation(), 4);
+        assert_eq!(
+            automaton.to_grid().0,
+            grid(".....\n..#..\n..#..\n..#..\n.....")
+        );
+        assert_eq!(automaton.count(|c| *c == '#'), 3);
+    }
+
+    #[test]
+    fn test_wrapping_glider() {
+        let start = grid(".#....\n..#...\n###...\n......\n......\n......");
+        let mut automaton = Automaton::new(start.clone(), Neighbourhood::Eight, Edges::Wrapping);
+
+        // A glider moves one cell diagonally every four generations, so it's back where it
+        // started after going all the way round
+        automaton.run(24, life);
+        assert_eq!(automaton.to_grid().0, start);
+    }
+
+    #[test]
+    fn test_infinite_glider() {
+        let mut automaton = Automaton::new(
+            grid(".#.\n..#\n###"),
+            Neighbourhood::Eight,
+            Edges::Infinite { background: '.' },
+        );
+
+        automaton.run(8, life);
+        let (glider, corner) = automaton.to_grid();
+        assert_eq!(glider, grid(".#.\n..#\n###"));
+        assert_eq!(corner, GridCoordinate::new(2, 2));
+        assert_eq!(automaton.count(|c| *c == '#'), 5);
+        assert_eq!(automaton.get(GridCoordinate::new(-100, 7)), Some(&'.'));
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        // Water spreading down and sideways through open cells until it's filled everything it can
+        let mut automaton = Automaton::new(
+            grid("~...\n.#..\n.##.\n...#"),
+            Neighbourhood::Four,
+            Edges::Bounded,
+        );
+
+        let steps = automaton
+            .run_until_stable(100, |cell, neighbours| match cell {
+                '.' if neighbours.count(|n| *n == '~') > 0 => '~',
+                _ => *cell,
+            })
+            .unwrap();
+        assert_eq!(steps, 5);
+        assert_eq!(automaton.changed(), 0);
+        assert_eq!(automaton.to_grid().0, grid("~~~~\n~#~~\n~##~\n~~~#"));
+
+        let mut blinker =
+            Automaton::new(grid("...\n###\n..."), Neighbourhood::Eight, Edges::Bounded);
+        assert!(blinker.run_until_stable(10, life).is_err());
+    }
+
+    #[test]
+    fn test_custom_neighbourhood() {
+        // Each cell copies the one to its left, so the pattern scrolls right
+        let mut automaton = Automaton::new(
+            grid("ab.."),
+            Neighbourhood::Custom(vec![GridDelta::new(0, -1)]),
+            Edges::Wrapping,
+        );
+
+        automaton.step(|_, neighbours| *neighbours.get(0).unwrap());
+        assert_eq!(automaton.to_grid().0, grid(".ab."));
+    }
+}
@@ -0,0 +1,344 @@
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+/// A set of small numbers, stored as one bit per number
+///
+/// By default it holds `0..64` in a single `u64`, which is enough for most "which valves are
+/// open" style states. A wider set can hold `0..64 * WORDS`, so `BitSet<2>` goes up to 127. It's
+/// `Copy` and hashable either way, so it works well as part of a cache key.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitSet<const WORDS: usize = 1> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    /// The number of values the set can hold, from zero up to one less than this
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    /// A set of everything from zero up to, but not including, `count`
+    ///
+    /// Panics if `count` is more than the capacity.
+    pub fn full(count: usize) -> Self {
+        assert!(
+            count <= Self::CAPACITY,
+            "BitSet can't hold {} values",
+            count
+        );
+
+        let mut set = Self::new();
+        for (idx, word) in set.words.iter_mut().enumerate() {
+            let bits = count.saturating_sub(idx * 64).min(64);
+            *word = if bits == 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+        }
+        set
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// Add a value, returning false if it was already there
+    ///
+    /// Panics if the value doesn't fit in the set.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(
+            value < Self::CAPACITY,
+            "{} doesn't fit in the BitSet",
+            value
+        );
+
+        let added = !self.contains(value);
+        self.words[value / 64] |= 1 << (value % 64);
+        added
+    }
+
+    /// Remove a value, returning false if it wasn't there
+    pub fn remove(&mut self, value: usize) -> bool {
+        let removed = self.contains(value);
+        if removed {
+            self.words[value / 64] &= !(1 << (value % 64));
+        }
+        removed
+    }
+
+    /// A copy of the set with the value added
+    pub fn with(mut self, value: usize) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// A copy of the set with the value removed
+    pub fn without(mut self, value: usize) -> Self {
+        self.remove(value);
+        self
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+
+    /// The smallest value in the set
+    pub fn first(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .find(|(_, word)| **word != 0)
+            .map(|(idx, word)| idx * 64 + word.trailing_zeros() as usize)
+    }
+
+    /// Iterate over the values in the set, smallest first
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, word)| {
+            let mut remaining = *word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                Some(idx * 64 + bit)
+            })
+        })
+    }
+
+    /// Every subset of this set, from the whole set down to the empty one
+    ///
+    /// There are `2^len` of them, so this is only sensible for fairly small sets.
+    pub fn subsets(&self) -> impl Iterator<Item = Self> {
+        let set = *self;
+        let mut next = Some(set);
+        std::iter::from_fn(move || {
+            let current = next?;
+            // Taking one off a subset and masking it gives the next subset down, the usual
+            // submask trick, and we stop once we've handed out the empty set
+            next = (!current.is_empty()).then(|| current.decremented().intersection(&set));
+            Some(current)
+        })
+    }
+
+    /// Every way to split this set between two, where each value goes to exactly one side
+    ///
+    /// Each split only turns up once, with the smallest value always on the left, which is what's
+    /// needed to share out work between two identical agents.
+    pub fn disjoint_pairs(&self) -> impl Iterator<Item = (Self, Self)> {
+        let set = *self;
+        let first = self.first();
+        self.subsets()
+            .filter(move |left| first.is_none_or(|first| left.contains(first)))
+            .map(move |left| (left, set.difference(&left)))
+    }
+
+    fn zip_with(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let mut result = *self;
+        for (word, other) in result.words.iter_mut().zip(other.words) {
+            *word = op(*word, other);
+        }
+        result
+    }
+
+    /// The set read as one big number, minus one, wrapping round
+    fn decremented(&self) -> Self {
+        let mut result = *self;
+        for word in result.words.iter_mut() {
+            let (value, borrowed) = word.overflowing_sub(1);
+            *word = value;
+            if !borrowed {
+                break;
+            }
+        }
+        result
+    }
+}
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize> std::fmt::Debug for BitSet<WORDS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const WORDS: usize> Extend<usize> for BitSet<WORDS> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<const WORDS: usize> BitOr for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(&other)
+    }
+}
+
+impl<const WORDS: usize> BitAnd for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(&other)
+    }
+}
+
+impl<const WORDS: usize> BitXor for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        self.symmetric_difference(&other)
+    }
+}
+
+impl<const WORDS: usize> Sub for BitSet<WORDS> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(&other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set: BitSet = BitSet::new();
+
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(63));
+        assert!(set.contains(3));
+        assert!(!set.contains(4));
+        assert!(!set.contains(1000));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.remove(3));
+        assert!(!set.remove(3));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![63]);
+        assert_eq!(set.without(63), BitSet::new());
+    }
+
+    #[test]
+    fn test_wide() {
+        let mut set: BitSet<2> = [1, 64, 127].into_iter().collect();
+
+        assert_eq!(BitSet::<2>::CAPACITY, 128);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 64, 127]);
+        assert_eq!(set.first(), Some(1));
+        set.remove(1);
+        assert_eq!(set.first(), Some(64));
+
+        assert_eq!(BitSet::<2>::full(70).len(), 70);
+        assert_eq!(BitSet::<2>::full(128).len(), 128);
+        assert!(BitSet::<2>::full(70).contains(69));
+        assert!(!BitSet::<2>::full(70).contains(70));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: BitSet = [1, 2, 3].into_iter().collect();
+        let b: BitSet = [3, 4].into_iter().collect();
+
+        assert_eq!(a | b, [1, 2, 3, 4].into_iter().collect());
+        assert_eq!(a & b, [3].into_iter().collect());
+        assert_eq!(a - b, [1, 2].into_iter().collect());
+        assert_eq!(a ^ b, [1, 2, 4].into_iter().collect());
+        assert!((a & b).is_subset(&a));
+        assert!(a.is_superset(&(a - b)));
+        assert!((a - b).is_disjoint(&b));
+        assert_eq!(format!("{:?}", b), "{3, 4}");
+    }
+
+    #[test]
+    fn test_subsets() {
+        let set: BitSet = [0, 2, 5].into_iter().collect();
+        let subsets: Vec<Vec<usize>> = set.subsets().map(|s| s.iter().collect()).collect();
+
+        assert_eq!(subsets.len(), 8);
+        assert_eq!(subsets[0], vec![0, 2, 5]);
+        assert_eq!(subsets[7], Vec::<usize>::new());
+        assert!(set.subsets().all(|subset| subset.is_subset(&set)));
+
+        // Subsets that cross from one word to the next
+        let wide: BitSet<2> = [63, 64].into_iter().collect();
+        assert_eq!(wide.subsets().count(), 4);
+
+        assert_eq!(BitSet::<1>::new().subsets().count(), 1);
+    }
+
+    #[test]
+    fn test_disjoint_pairs() {
+        let set: BitSet = [1, 2, 3].into_iter().collect();
+        let pairs: Vec<_> = set.disjoint_pairs().collect();
+
+        assert_eq!(pairs.len(), 4);
+        for (left, right) in pairs {
+            assert!(left.contains(1));
+            assert!(left.is_disjoint(&right));
+            assert_eq!(left | right, set);
+        }
+
+        let empty = BitSet::<1>::new();
+        assert_eq!(
+            empty.disjoint_pairs().collect::<Vec<_>>(),
+            vec![(empty, empty)]
+        );
+    }
+}
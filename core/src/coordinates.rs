@@ -2,14 +2,113 @@ use crate::enums::DirectionShift;
 use crate::prelude::*;
 use num_traits::{FromPrimitive, PrimInt, Zero};
 use std::cmp::PartialOrd;
-use std::ops::{Add, AddAssign};
+use std::ops::{Add, AddAssign, Mul, Neg};
 
+mod grid;
+
+pub use grid::Grid;
+
+/// Anything that stores cells in rows and columns and can be indexed by a `GridCoordinate`
+///
+/// This is implemented for the classic `Vec<Vec<_>>` as well as the flat `Grid<_>`, so the
+/// `GridCoordinate` helpers work the same way regardless of which one a day is using.
+pub trait GridStorage {
+    type Cell;
+
+    /// The (height, width) of the grid
+    fn dimensions(&self) -> (usize, usize);
+
+    /// Get a cell, without any bounds checks beyond what the underlying storage does
+    fn cell(&self, row: usize, col: usize) -> &Self::Cell;
+
+    fn cell_mut(&mut self, row: usize, col: usize) -> &mut Self::Cell;
+}
+
+impl<U> GridStorage for [Vec<U>] {
+    type Cell = U;
+
+    fn dimensions(&self) -> (usize, usize) {
+        match self.first() {
+            Some(row) => (self.len(), row.len()),
+            None => (0, 0),
+        }
+    }
+
+    fn cell(&self, row: usize, col: usize) -> &U {
+        &self[row][col]
+    }
+
+    fn cell_mut(&mut self, row: usize, col: usize) -> &mut U {
+        &mut self[row][col]
+    }
+}
+
+impl<U> GridStorage for Vec<Vec<U>> {
+    type Cell = U;
+
+    fn dimensions(&self) -> (usize, usize) {
+        self.as_slice().dimensions()
+    }
+
+    fn cell(&self, row: usize, col: usize) -> &U {
+        &self[row][col]
+    }
+
+    fn cell_mut(&mut self, row: usize, col: usize) -> &mut U {
+        &mut self[row][col]
+    }
+}
+
+/// A position on a grid
+///
+/// The convention throughout is (row, column), with row 0 at the top and rows growing
+/// downwards, and column 0 on the left with columns growing to the right. This matches indexing
+/// into a `Vec<Vec<_>>` as `grid[row][column]`, so "up" (or "north") decreases the row.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GridCoordinate<T> {
     pub column: T,
     pub row: T,
 }
 
+/// A step on a grid, as a change in row and a change in column
+///
+/// This follows the same (row, column) convention as `GridCoordinate`, so the delta for "up" is
+/// `GridDelta { row: -1, column: 0 }`. Having a separate type, rather than a tuple, means a delta
+/// can't accidentally be read as an (x, y) pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GridDelta<T = i32> {
+    pub row: T,
+    pub column: T,
+}
+
+impl<T> GridDelta<T> {
+    pub const fn new(row: T, column: T) -> Self {
+        Self { row, column }
+    }
+}
+
+impl<T: PrimInt> Mul<T> for GridDelta<T> {
+    type Output = Self;
+
+    fn mul(self, steps: T) -> Self::Output {
+        Self {
+            row: self.row * steps,
+            column: self.column * steps,
+        }
+    }
+}
+
+impl<T: PrimInt + Neg<Output = T>> Neg for GridDelta<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            row: -self.row,
+            column: -self.column,
+        }
+    }
+}
+
 impl<T> GridCoordinate<T>
 where
     T: PrimInt + PartialOrd,
@@ -18,6 +117,15 @@ where
         Self { row, column }
     }
 
+    /// Convert the coordinate into one backed by another integer type, such as going from the
+    /// `usize` coordinates a `Grid` hands out to the `i32` coordinates a day is working with
+    pub fn cast<U: PrimInt>(&self) -> Option<GridCoordinate<U>> {
+        Some(GridCoordinate::new(
+            U::from(self.row)?,
+            U::from(self.column)?,
+        ))
+    }
+
     fn within_unsigned(&self, height: T, width: T) -> bool {
         self.row < height && self.column < width
     }
@@ -48,13 +156,14 @@ impl<T> GridCoordinate<T>
 where
     T: PrimInt + PartialOrd + FromPrimitive,
 {
-    pub fn within_grid<U>(&self, grid: &[Vec<U>]) -> bool {
-        if grid.is_empty() || grid[0].is_empty() {
+    pub fn within_grid<G: GridStorage + ?Sized>(&self, grid: &G) -> bool {
+        let (height, width) = grid.dimensions();
+        if height == 0 || width == 0 {
             return false;
         }
 
-        let height = T::from_usize(grid.len()).unwrap();
-        let width = T::from_usize(grid[0].len()).unwrap();
+        let height = T::from_usize(height).unwrap();
+        let width = T::from_usize(width).unwrap();
 
         if T::min_value() < Zero::zero() {
             self.within_signed(height, width)
@@ -63,7 +172,7 @@ where
         }
     }
 
-    pub fn get<'a, U>(&self, grid: &'a [Vec<U>]) -> Option<&'a U> {
+    pub fn get<'a, G: GridStorage + ?Sized>(&self, grid: &'a G) -> Option<&'a G::Cell> {
         if !self.within_grid(grid) {
             return None;
         }
@@ -72,21 +181,32 @@ where
         let row = self.row.to_usize().unwrap();
         let col = self.column.to_usize().unwrap();
 
-        Some(&grid[row][col])
+        Some(grid.cell(row, col))
     }
 
-    pub fn set<U>(&self, grid: &mut [Vec<U>], value: U) -> Result<()> {
+    pub fn get_mut<'a, G: GridStorage + ?Sized>(&self, grid: &'a mut G) -> Option<&'a mut G::Cell> {
         if !self.within_grid(grid) {
-            return Err(error!("Out of bounds"));
+            return None;
         }
+
         // Safe to unwrap since we already checked bounds with within_grid
         let row = self.row.to_usize().unwrap();
         let col = self.column.to_usize().unwrap();
-        grid[row][col] = value;
+
+        Some(grid.cell_mut(row, col))
+    }
+
+    pub fn set<G: GridStorage + ?Sized>(&self, grid: &mut G, value: G::Cell) -> Result<()> {
+        let cell = self.get_mut(grid).ok_or(error!("Out of bounds"))?;
+        *cell = value;
 
         Ok(())
     }
 
+    /// Move one step in the given direction, following the same (row, column) convention as
+    /// `as_vector` on the direction enums
+    ///
+    /// Returns `None` if an unsigned coordinate would go below zero.
     pub fn shifted<D: DirectionShift>(&self, direction: D) -> Option<Self> {
         let direction = direction.to_grid_direction();
         let is_signed = T::min_value() < Zero::zero();
@@ -123,21 +243,21 @@ where
     }
 }
 
-impl<T: PrimInt> Add<(T, T)> for GridCoordinate<T> {
+impl<T: PrimInt> Add<GridCoordinate<T>> for GridCoordinate<T> {
     type Output = Self;
 
-    fn add(self, other: (T, T)) -> Self::Output {
+    fn add(self, other: GridCoordinate<T>) -> Self::Output {
         Self {
-            row: self.row + other.0,
-            column: self.column + other.1,
+            row: self.row + other.row,
+            column: self.column + other.column,
         }
     }
 }
 
-impl<T: PrimInt> Add<GridCoordinate<T>> for GridCoordinate<T> {
+impl<T: PrimInt> Add<GridDelta<T>> for GridCoordinate<T> {
     type Output = Self;
 
-    fn add(self, other: GridCoordinate<T>) -> Self::Output {
+    fn add(self, other: GridDelta<T>) -> Self::Output {
         Self {
             row: self.row + other.row,
             column: self.column + other.column,
@@ -145,10 +265,10 @@ impl<T: PrimInt> Add<GridCoordinate<T>> for GridCoordinate<T> {
     }
 }
 
-impl<T: PrimInt> AddAssign<(T, T)> for GridCoordinate<T> {
-    fn add_assign(&mut self, other: (T, T)) {
-        self.row = self.row + other.0;
-        self.column = self.column + other.1;
+impl<T: PrimInt> AddAssign<GridDelta<T>> for GridCoordinate<T> {
+    fn add_assign(&mut self, other: GridDelta<T>) {
+        self.row = self.row + other.row;
+        self.column = self.column + other.column;
     }
 }
 
@@ -298,33 +418,33 @@ mod tests {
     }
 
     #[test]
-    fn test_basic_tuple_addition() {
+    fn test_basic_delta_addition() {
         let coord_i32 = GridCoordinate::new(1, 2);
         let coord_u32 = GridCoordinate::new(5_u32, 7_u32);
 
-        assert_eq!(coord_i32 + (2, 3), GridCoordinate::new(3, 5));
-        assert_eq!(coord_u32 + (2, 3), GridCoordinate::new(7, 10));
+        assert_eq!(coord_i32 + GridDelta::new(2, 3), GridCoordinate::new(3, 5));
+        assert_eq!(coord_u32 + GridDelta::new(2, 3), GridCoordinate::new(7, 10));
     }
 
     #[test]
-    fn test_zero_tuple_addition() {
+    fn test_zero_delta_addition() {
         let coord_i32 = GridCoordinate::new(1, 2);
         let coord_u32 = GridCoordinate::new(5_u32, 7_u32);
 
-        assert_eq!(coord_i32 + (0, 0), coord_i32);
-        assert_eq!(coord_u32 + (0, 0), coord_u32);
+        assert_eq!(coord_i32 + GridDelta::new(0, 0), coord_i32);
+        assert_eq!(coord_u32 + GridDelta::new(0, 0), coord_u32);
     }
 
     #[test]
-    fn test_negative_tuple_addition() {
+    fn test_negative_delta_addition() {
         let coord = GridCoordinate::new(1, 2);
 
-        assert_eq!(coord + (-1, -2), GridCoordinate::new(0, 0));
-        assert_eq!(coord + (-5, -7), GridCoordinate::new(-4, -5));
+        assert_eq!(coord + GridDelta::new(-1, -2), GridCoordinate::new(0, 0));
+        assert_eq!(coord + GridDelta::new(-5, -7), GridCoordinate::new(-4, -5));
     }
 
     #[test]
-    fn test_large_tuple_addition_signed() {
+    fn test_large_delta_addition_signed() {
         let coord = GridCoordinate::new(1_i32, 2_i32);
         let large_value = i32::MAX - 2;
 
@@ -333,11 +453,14 @@ mod tests {
             2 + (large_value - 1), // 2 + (MAX-3) = MAX-1
         );
 
-        assert_eq!(coord + (large_value, large_value - 1), expected);
+        assert_eq!(
+            coord + GridDelta::new(large_value, large_value - 1),
+            expected
+        );
     }
 
     #[test]
-    fn test_large_tuple_addition_unsigned() {
+    fn test_large_delta_addition_unsigned() {
         let coord = GridCoordinate::new(5_u32, 7_u32);
         let large_value = u32::MAX - 10;
 
@@ -346,21 +469,21 @@ mod tests {
             7 + (large_value - 7), // 7 + (MAX-17) = MAX-10
         );
 
-        assert_eq!(coord + (2, large_value - 7), expected);
+        assert_eq!(coord + GridDelta::new(2, large_value - 7), expected);
     }
 
     #[test]
     #[should_panic]
-    fn test_tuple_addition_overflow_unsigned() {
+    fn test_delta_addition_overflow_unsigned() {
         let coord = GridCoordinate::new(1_u32, 2_u32);
-        let _ = coord + (u32::MAX, u32::MAX);
+        let _ = coord + GridDelta::new(u32::MAX, u32::MAX);
     }
 
     #[test]
     #[should_panic]
-    fn test_tuple_addition_overflow_signed() {
+    fn test_delta_addition_overflow_signed() {
         let coord = GridCoordinate::new(i32::MAX, i32::MAX);
-        let _ = coord + (1, 1);
+        let _ = coord + GridDelta::new(1, 1);
     }
 
     #[test]
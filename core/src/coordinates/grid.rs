@@ -0,0 +1,432 @@
+use super::{GridCoordinate, GridStorage};
+use crate::prelude::*;
+use num_traits::PrimInt;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+mod transform;
+
+/// A dense, rectangular grid with the cells stored row by row in a single `Vec`
+///
+/// Cells are addressed with a `GridCoordinate` of any integer type, so a day can keep using
+/// `GridCoordinate<i32>` for walking around and still index straight into the grid. Anything
+/// outside the grid (including negative coordinates) is simply `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid of the given size, with every cell set to `value`
+    pub fn new(height: usize, width: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Create a grid from cells that are already laid out row by row
+    pub fn from_vec(height: usize, width: usize, cells: Vec<T>) -> Result<Self> {
+        if cells.len() != width * height {
+            return Err(error!(
+                "Expected {} cells for a {}x{} grid, got {}",
+                width * height,
+                height,
+                width,
+                cells.len()
+            ));
+        }
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Create a grid from nested rows, which all need to be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        let mut cells = Vec::with_capacity(width * height);
+        for (row_idx, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(error!(
+                    "Row {} has {} cells, expected {}",
+                    row_idx,
+                    row.len(),
+                    width
+                ));
+            }
+            cells.extend(row);
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Parse the puzzle text into a grid, mapping each character through `f`
+    ///
+    /// Trailing newlines are ignored, but every line needs to be the same length and every
+    /// character needs to map to a cell.
+    pub fn parse<F>(input: &str, mut f: F) -> Result<Self>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+
+        for (row, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let mut line_width = 0;
+            for (col, c) in line.chars().enumerate() {
+                let cell =
+                    f(c).ok_or_else(|| error!("Unknown character {:?} at ({}, {})", c, row, col))?;
+                cells.push(cell);
+                line_width += 1;
+            }
+
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(error!(
+                        "Row {} has {} cells, expected {}",
+                        row, line_width, width
+                    ));
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether the coordinate is inside the grid
+    pub fn contains<C: PrimInt>(&self, coord: &GridCoordinate<C>) -> bool {
+        self.index_of(coord).is_some()
+    }
+
+    pub fn get<C: PrimInt>(&self, coord: &GridCoordinate<C>) -> Option<&T> {
+        self.index_of(coord).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut<C: PrimInt>(&mut self, coord: &GridCoordinate<C>) -> Option<&mut T> {
+        self.index_of(coord).map(|idx| &mut self.cells[idx])
+    }
+
+    pub fn set<C: PrimInt>(&mut self, coord: &GridCoordinate<C>, value: T) -> Result<()> {
+        let cell = self.get_mut(coord).ok_or(error!("Out of bounds"))?;
+        *cell = value;
+
+        Ok(())
+    }
+
+    /// A single row of the grid as a slice
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row >= self.height {
+            return None;
+        }
+        Some(&self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks_exact panics on a zero width, which an empty grid would have
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    /// Iterate over all the cells, row by row, along with their coordinates
+    pub fn iter(&self) -> impl Iterator<Item = (GridCoordinate<usize>, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(idx, cell)| (GridCoordinate::new(idx / width, idx % width), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (GridCoordinate<usize>, &mut T)> {
+        let width = self.width;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(idx, cell)| (GridCoordinate::new(idx / width, idx % width), cell))
+    }
+
+    /// All the coordinates in the grid, row by row
+    pub fn coordinates(&self) -> impl Iterator<Item = GridCoordinate<usize>> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |idx| GridCoordinate::new(idx / width, idx % width))
+    }
+
+    /// Find the first coordinate (row by row) where the predicate matches
+    pub fn position<P>(&self, mut predicate: P) -> Option<GridCoordinate<usize>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find_map(|(coord, cell)| predicate(cell).then_some(coord))
+    }
+
+    /// Find all the coordinates where the predicate matches
+    pub fn positions<'a, P>(
+        &'a self,
+        mut predicate: P,
+    ) -> impl Iterator<Item = GridCoordinate<usize>> + 'a
+    where
+        P: FnMut(&T) -> bool + 'a,
+    {
+        self.iter()
+            .filter_map(move |(coord, cell)| predicate(cell).then_some(coord))
+    }
+
+    /// Find the first occurrence of a marker, such as the start or end of a maze
+    pub fn find(&self, value: &T) -> Option<GridCoordinate<usize>>
+    where
+        T: PartialEq,
+    {
+        self.position(|cell| cell == value)
+    }
+
+    /// Map every cell into a new grid of the same size
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index_of<C: PrimInt>(&self, coord: &GridCoordinate<C>) -> Option<usize> {
+        // Negative coordinates fail the conversion, so they are out of bounds as well
+        let row = coord.row.to_usize()?;
+        let col = coord.column.to_usize()?;
+        (row < self.height && col < self.width).then(|| row * self.width + col)
+    }
+}
+
+impl<T> GridStorage for Grid<T> {
+    type Cell = T;
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> &T {
+        &self.cells[row * self.width + col]
+    }
+
+    fn cell_mut(&mut self, row: usize, col: usize) -> &mut T {
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl<T, C: PrimInt> Index<GridCoordinate<C>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: GridCoordinate<C>) -> &T {
+        match self.index_of(&coord) {
+            Some(idx) => &self.cells[idx],
+            None => panic!(
+                "Coordinate out of bounds for a {}x{} grid",
+                self.height, self.width
+            ),
+        }
+    }
+}
+
+impl<T, C: PrimInt> IndexMut<GridCoordinate<C>> for Grid<T> {
+    fn index_mut(&mut self, coord: GridCoordinate<C>) -> &mut T {
+        match self.index_of(&coord) {
+            Some(idx) => &mut self.cells[idx],
+            None => panic!(
+                "Coordinate out of bounds for a {}x{} grid",
+                self.height, self.width
+            ),
+        }
+    }
+}
+
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = AdventError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s, |c| T::try_from(c).ok())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Node {
+        Wall,
+        Floor,
+        Start,
+        End,
+    }
+
+    impl TryFrom<char> for Node {
+        type Error = AdventError;
+
+        fn try_from(c: char) -> Result<Self> {
+            match c {
+                '#' => Ok(Node::Wall),
+                '.' => Ok(Node::Floor),
+                'S' => Ok(Node::Start),
+                'E' => Ok(Node::End),
+                _ => Err(AdventError::InvalidInput),
+            }
+        }
+    }
+
+    impl fmt::Display for Node {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let c = match self {
+                Node::Wall => '#',
+                Node::Floor => '.',
+                Node::Start => 'S',
+                Node::End => 'E',
+            };
+            write!(f, "{}", c)
+        }
+    }
+
+    const MAZE: &str = "####\n#S.#\n#.E#\n####\n";
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<Node> = MAZE.parse().unwrap();
+
+        assert_eq!(grid.height(), 4);
+        assert_eq!(grid.width(), 4);
+        assert_eq!(grid.get(&GridCoordinate::new(1, 1)), Some(&Node::Start));
+        assert_eq!(grid.get(&GridCoordinate::new(2, 2)), Some(&Node::End));
+    }
+
+    #[test]
+    fn test_parse_with_mapping() {
+        let grid = Grid::parse("12\n34", |c| c.to_digit(10)).unwrap();
+
+        assert_eq!(grid, Grid::from_vec(2, 2, vec![1, 2, 3, 4]).unwrap());
+    }
+
+    #[test]
+    fn test_parse_unknown_character() {
+        let err = Grid::<Node>::from_str("#.\n#x").unwrap_err();
+        assert_eq!(err.to_string(), "Error: Unknown character 'x' at (1, 1)");
+    }
+
+    #[test]
+    fn test_parse_ragged_rows() {
+        assert!(Grid::<Node>::from_str("##\n#").is_err());
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn test_get_out_of_bounds() {
+        let grid: Grid<Node> = MAZE.parse().unwrap();
+
+        assert_eq!(grid.get(&GridCoordinate::new(4usize, 0)), None);
+        assert_eq!(grid.get(&GridCoordinate::new(0usize, 4)), None);
+        assert_eq!(grid.get(&GridCoordinate::new(-1i32, 0)), None);
+        assert!(!grid.contains(&GridCoordinate::new(0i32, -1)));
+    }
+
+    #[test]
+    fn test_set_and_index() {
+        let mut grid = Grid::new(2, 3, 0);
+        grid.set(&GridCoordinate::new(1, 2), 5).unwrap();
+        grid[GridCoordinate::new(0i32, 1i32)] = 7;
+
+        assert_eq!(grid[GridCoordinate::new(1usize, 2usize)], 5);
+        assert_eq!(grid.row(0), Some(&[0, 7, 0][..]));
+        assert!(grid.set(&GridCoordinate::new(2, 0), 1).is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let grid: Grid<Node> = MAZE.parse().unwrap();
+
+        assert_eq!(grid.find(&Node::Start), Some(GridCoordinate::new(1, 1)));
+        assert_eq!(grid.find(&Node::End), Some(GridCoordinate::new(2, 2)));
+        assert_eq!(grid.positions(|node| *node == Node::Floor).count(), 2);
+    }
+
+    #[test]
+    fn test_iter() {
+        let grid = Grid::from_rows(vec![vec!['a', 'b'], vec!['c', 'd']]).unwrap();
+
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![
+                (GridCoordinate::new(0, 0), &'a'),
+                (GridCoordinate::new(0, 1), &'b'),
+                (GridCoordinate::new(1, 0), &'c'),
+                (GridCoordinate::new(1, 1), &'d'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let grid: Grid<Node> = MAZE.parse().unwrap();
+
+        assert_eq!(grid.to_string(), MAZE);
+    }
+
+    #[test]
+    fn test_coordinate_helpers() {
+        let mut grid: Grid<Node> = MAZE.parse().unwrap();
+        let start = grid.find(&Node::Start).unwrap().cast::<i32>().unwrap();
+
+        let right = start.shifted(GridDirection::Right).unwrap();
+        assert_eq!(right.get(&grid), Some(&Node::Floor));
+
+        right.set(&mut grid, Node::Wall).unwrap();
+        assert_eq!(grid[right], Node::Wall);
+
+        let outside = GridCoordinate::new(-1, 0);
+        assert_eq!(outside.get(&grid), None);
+    }
+}
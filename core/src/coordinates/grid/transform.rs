@@ -0,0 +1,198 @@
+use super::Grid;
+use crate::enums::DirectionShift;
+
+impl<T> Grid<T> {
+    /// Swap rows and columns, so the cell at (row, column) ends up at (column, row)
+    pub fn transpose(&mut self) {
+        let height = self.height;
+        self.permute(self.width, height, |row, col| (col, row));
+    }
+
+    /// Turn the grid a quarter turn clockwise, so the top row becomes the right hand column
+    pub fn rotate_clockwise(&mut self) {
+        let height = self.height;
+        self.permute(self.width, height, |row, col| (col, height - 1 - row));
+    }
+
+    /// Turn the grid a quarter turn anticlockwise, so the top row becomes the left hand column
+    pub fn rotate_counter_clockwise(&mut self) {
+        let width = self.width;
+        self.permute(width, self.height, |row, col| (width - 1 - col, row));
+    }
+
+    /// Turn the grid upside down, which is the same as flipping it both ways
+    pub fn rotate_180(&mut self) {
+        self.cells.reverse();
+    }
+
+    /// Mirror the grid left to right, reversing every row
+    pub fn flip_horizontal(&mut self) {
+        for row in self.cells.chunks_exact_mut(self.width.max(1)) {
+            row.reverse();
+        }
+    }
+
+    /// Mirror the grid top to bottom, reversing the order of the rows
+    pub fn flip_vertical(&mut self) {
+        let width = self.width;
+        for row in 0..self.height / 2 {
+            let (top, bottom) = self.cells.split_at_mut((self.height - 1 - row) * width);
+            top[row * width..(row + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+    }
+
+    /// Slide every movable cell as far as it'll go in the direction, like tilting the grid
+    ///
+    /// A movable cell keeps going through empty cells until it hits the edge, a cell that's
+    /// neither movable nor empty, or another movable cell that has already stopped. Cells are
+    /// swapped rather than copied, so whatever was in the empty cell ends up where the movable one
+    /// started.
+    pub fn slide<D, M, E>(&mut self, direction: D, is_movable: M, is_empty: E)
+    where
+        D: DirectionShift,
+        M: Fn(&T) -> bool,
+        E: Fn(&T) -> bool,
+    {
+        let delta = direction.to_grid_direction().as_vector();
+        let (height, width) = (self.height as i64, self.width as i64);
+        let (d_row, d_col) = (delta.row as i64, delta.column as i64);
+        let inside = |row: i64, col: i64| (0..height).contains(&row) && (0..width).contains(&col);
+
+        let mut line = Vec::with_capacity(self.height.max(self.width));
+        for (row, col) in (0..height).flat_map(|row| (0..width).map(move |col| (row, col))) {
+            // Each line starts at a cell with nothing behind it
+            if inside(row - d_row, col - d_col) {
+                continue;
+            }
+
+            line.clear();
+            let (mut r, mut c) = (row, col);
+            while inside(r, c) {
+                line.push((r * width + c) as usize);
+                r += d_row;
+                c += d_col;
+            }
+
+            // Work back from the far end, keeping track of where the next movable cell would stop
+            let mut free: Option<usize> = None;
+            for pos in (0..line.len()).rev() {
+                let cell = &self.cells[line[pos]];
+                if is_movable(cell) {
+                    if let Some(target) = free {
+                        self.cells.swap(line[pos], line[target]);
+                        // Everything between here and the target was empty, so the next one
+                        // along is too
+                        free = Some(target - 1);
+                    }
+                } else if is_empty(cell) {
+                    free.get_or_insert(pos);
+                } else {
+                    free = None;
+                }
+            }
+        }
+    }
+
+    /// Move every cell to a new spot, giving the grid a new shape, without a second buffer
+    ///
+    /// `destination` maps a (row, column) in the current grid to one in the new grid, and has to
+    /// hit every cell exactly once. Each cycle of the permutation is followed round, swapping as
+    /// it goes, so only a `bool` per cell is needed on the side.
+    fn permute<F>(&mut self, height: usize, width: usize, destination: F)
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let old_width = self.width;
+        let target = |idx: usize| {
+            let (row, col) = destination(idx / old_width, idx % old_width);
+            row * width + col
+        };
+
+        let mut visited = vec![false; self.cells.len()];
+        for start in 0..self.cells.len() {
+            if visited[start] {
+                continue;
+            }
+            // The cell sitting at `start` always belongs at `current`'s target
+            let mut current = start;
+            visited[start] = true;
+            loop {
+                let next = target(current);
+                if next == start {
+                    break;
+                }
+                self.cells.swap(start, next);
+                visited[next] = true;
+                current = next;
+            }
+        }
+
+        self.height = height;
+        self.width = width;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn grid(s: &str) -> Grid<char> {
+        Grid::parse(s, Some).unwrap()
+    }
+
+    #[test]
+    fn test_transpose() {
+        let mut g = grid("abc\ndef");
+        g.transpose();
+        assert_eq!(g, grid("ad\nbe\ncf"));
+
+        let mut square = grid("ab\ncd");
+        square.transpose();
+        assert_eq!(square, grid("ac\nbd"));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut g = grid("abc\ndef");
+
+        g.rotate_clockwise();
+        assert_eq!(g, grid("da\neb\nfc"));
+        g.rotate_clockwise();
+        assert_eq!(g, grid("fed\ncba"));
+        g.rotate_180();
+        assert_eq!(g, grid("abc\ndef"));
+        g.rotate_counter_clockwise();
+        assert_eq!(g, grid("cf\nbe\nad"));
+    }
+
+    #[test]
+    fn test_flip() {
+        let mut g = grid("abc\ndef\nghi");
+
+        g.flip_horizontal();
+        assert_eq!(g, grid("cba\nfed\nihg"));
+        g.flip_vertical();
+        assert_eq!(g, grid("ihg\nfed\ncba"));
+
+        let mut even = grid("ab\ncd\nef\ngh");
+        even.flip_vertical();
+        assert_eq!(even, grid("gh\nef\ncd\nab"));
+    }
+
+    #[test]
+    fn test_slide() {
+        let platform = "O.#.\n..O.\nO..O";
+        let tilted = |direction: GridDirection| {
+            let mut g = grid(platform);
+            g.slide(direction, |c| *c == 'O', |c| *c == '.');
+            g
+        };
+
+        assert_eq!(tilted(GridDirection::Up), grid("O.#O\nO.O.\n...."));
+        assert_eq!(tilted(GridDirection::Down), grid("..#.\nO...\nO.OO"));
+        assert_eq!(tilted(GridDirection::Left), grid("O.#.\nO...\nOO.."));
+        assert_eq!(tilted(GridDirection::Right), grid(".O#.\n...O\n..OO"));
+        assert_eq!(tilted(GridDirection::DownRight), grid("..#.\n..O.\nO.OO"));
+    }
+}
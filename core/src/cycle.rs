@@ -0,0 +1,244 @@
+use crate::prelude::*;
+use std::hash::Hash;
+
+/// Where a sequence of states starts repeating
+///
+/// Most "what does it look like after a billion iterations" puzzles settle into a loop long before
+/// then. The tail is how long it takes to get into the loop, and the period is how long it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// How many steps it takes to reach the first state in the loop
+    pub tail: usize,
+    /// How many steps it takes to get back round to the same state
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`
+    ///
+    /// This is always less than `tail + period`, so it's quick to simulate.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.period
+        }
+    }
+
+    /// The state after `n` steps, simulating only as far as the equivalent step in the first loop
+    pub fn state_after<S: Clone>(&self, start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        let mut state = start;
+        for _ in 0..self.equivalent_step(n) {
+            state = step(&state);
+        }
+        state
+    }
+}
+
+/// The states seen on the way round a loop, found by remembering each one
+#[derive(Debug, Clone)]
+pub struct CycleHistory<S> {
+    pub cycle: Cycle,
+    /// Every state from the start up to just before the loop comes back round
+    states: Vec<S>,
+}
+
+impl<S> CycleHistory<S> {
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The state after `n` steps, without any further simulation
+    pub fn state_after(&self, n: usize) -> &S {
+        &self.states[self.cycle.equivalent_step(n)]
+    }
+}
+
+/// Floyd's tortoise and hare
+///
+/// Only ever holds on to two states, at the cost of stepping roughly three times as often as the
+/// hash based version.
+pub fn floyd<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // The hare goes twice as fast, so they meet somewhere in the loop
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    // The meeting point is a whole number of loops ahead of the start, so going at the same speed
+    // from the start and the meeting point they meet again at the start of the loop
+    let mut tail = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+/// Brent's algorithm
+///
+/// Like Floyd's, only holds on to two states, but finds the period directly and usually needs
+/// fewer steps to do it.
+pub fn brent<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Leave the tortoise in place and let the hare run ahead, teleporting the tortoise to it at
+    // every power of two, until the hare comes back round to it
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Start the hare a whole loop ahead, so they meet at the start of the loop
+    let mut tail = 0;
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+/// Find the loop by remembering every state until one repeats
+///
+/// Steps the fewest times and keeps every state, so `state_after` doesn't need to simulate
+/// anything, but the states have to be hashable and all of them are kept in memory.
+pub fn hashed<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S) -> CycleHistory<S> {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start;
+
+    loop {
+        if let Some(&tail) = seen.get(&state) {
+            let period = states.len() - tail;
+            return CycleHistory {
+                cycle: Cycle { tail, period },
+                states,
+            };
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps, skipping ahead as soon as a loop is found
+///
+/// This is the one to use for "after a billion spins" style puzzles. If `n` is reached before
+/// anything repeats, no loop needs to be found at all.
+pub fn state_after<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start;
+
+    while states.len() < n {
+        if let Some(&tail) = seen.get(&state) {
+            let cycle = Cycle {
+                tail,
+                period: states.len() - tail,
+            };
+            return states.swap_remove(cycle.equivalent_step(n));
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2, then loops round 3, 4, 5, 6, 7
+    fn step(value: &u32) -> u32 {
+        if *value == 7 {
+            3
+        } else {
+            value + 1
+        }
+    }
+
+    const EXPECTED: Cycle = Cycle { tail: 3, period: 5 };
+
+    #[test]
+    fn test_algorithms_agree() {
+        assert_eq!(floyd(0, step), EXPECTED);
+        assert_eq!(brent(0, step), EXPECTED);
+        assert_eq!(hashed(0, step).cycle, EXPECTED);
+    }
+
+    #[test]
+    fn test_no_tail() {
+        let expected = Cycle { tail: 0, period: 5 };
+
+        assert_eq!(floyd(4, step), expected);
+        assert_eq!(brent(4, step), expected);
+        assert_eq!(hashed(4, step).cycle, expected);
+
+        // A fixed point is a loop of one
+        let fixed = Cycle { tail: 0, period: 1 };
+        assert_eq!(floyd(1, |v: &u32| *v), fixed);
+        assert_eq!(brent(1, |v: &u32| *v), fixed);
+    }
+
+    #[test]
+    fn test_equivalent_step() {
+        assert_eq!(EXPECTED.equivalent_step(2), 2);
+        assert_eq!(EXPECTED.equivalent_step(3), 3);
+        assert_eq!(EXPECTED.equivalent_step(8), 3);
+        assert_eq!(EXPECTED.equivalent_step(1_000_000_000), 5);
+    }
+
+    #[test]
+    fn test_state_after() {
+        let history = hashed(0, step);
+        assert_eq!(history.states(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+
+        for n in [0, 2, 3, 7, 8, 12, 1_000_000_000] {
+            let mut expected = 0;
+            for _ in 0..EXPECTED.equivalent_step(n) {
+                expected = step(&expected);
+            }
+
+            assert_eq!(*history.state_after(n), expected);
+            assert_eq!(EXPECTED.state_after(0, step, n), expected);
+            assert_eq!(state_after(0, step, n), expected);
+        }
+    }
+
+    #[test]
+    fn test_state_after_before_loop() {
+        // Counting up never loops, but we stop long before it matters
+        assert_eq!(state_after(0u64, |v| v + 1, 10), 10);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::coordinates::GridDelta;
+
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum CompassDirection {
     North,
@@ -87,21 +89,11 @@ impl CompassDirection {
         }
     }
 
-    pub fn as_vector(&self) -> (i32, i32) {
-        // TODO: This doesn't match as_vector in GridDirection.. so need to make sure it's
-        // refactored to match!
-        // This is the right way, if we think of it as (row, column) and we start in upper-right
-        // corner
-        match self {
-            CompassDirection::North => (-1, 0),
-            CompassDirection::South => (1, 0),
-            CompassDirection::West => (0, -1),
-            CompassDirection::East => (0, 1),
-            CompassDirection::NorthWest => (-1, -1),
-            CompassDirection::NorthEast => (-1, 1),
-            CompassDirection::SouthWest => (1, -1),
-            CompassDirection::SouthEast => (1, 1),
-        }
+    /// Turn the direction into a unit `GridDelta`
+    ///
+    /// This is the same as `as_vector` on the matching `GridDirection`, so north is one row up.
+    pub fn as_vector(&self) -> GridDelta {
+        self.to_grid_direction().as_vector()
     }
 }
 
@@ -119,24 +111,25 @@ impl GridDirection {
         }
     }
 
-    /// Turn the direction into a "unit vector"
+    /// Turn the direction into a unit `GridDelta`
     ///
-    /// If we assume the grid system is (y, x) and the grid grows down and to the right, then "up"
-    /// is (0, -1) and "left" is (-1, 0) for example.
+    /// The grid is (row, column), growing down and to the right, the same as `GridCoordinate`.
+    /// So "up" is one row back, `GridDelta { row: -1, column: 0 }`, and "left" is one column
+    /// back, `GridDelta { row: 0, column: -1 }`.
     ///
-    /// Why (y, x)? Because when working with a grid of Vec<Vec<_>> the first value will be the
-    /// height (y) and the second value is the width (x) and it's simpler to think of it as
-    /// grid[y][x] to access a cell.
-    pub fn as_vector(&self) -> (i32, i32) {
+    /// Why (row, column)? Because when working with a grid of Vec<Vec<_>> the first index is the
+    /// row and the second is the column, and it's simpler to think of it as grid[row][column]
+    /// to access a cell.
+    pub fn as_vector(&self) -> GridDelta {
         match self {
-            GridDirection::Up => (0, -1),
-            GridDirection::Down => (0, 1),
-            GridDirection::Left => (-1, 0),
-            GridDirection::Right => (1, 0),
-            GridDirection::UpLeft => (-1, -1),
-            GridDirection::DownRight => (1, 1),
-            GridDirection::DownLeft => (-1, 1),
-            GridDirection::UpRight => (1, -1),
+            GridDirection::Up => GridDelta::new(-1, 0),
+            GridDirection::Down => GridDelta::new(1, 0),
+            GridDirection::Left => GridDelta::new(0, -1),
+            GridDirection::Right => GridDelta::new(0, 1),
+            GridDirection::UpLeft => GridDelta::new(-1, -1),
+            GridDirection::UpRight => GridDelta::new(-1, 1),
+            GridDirection::DownLeft => GridDelta::new(1, -1),
+            GridDirection::DownRight => GridDelta::new(1, 1),
         }
     }
 
@@ -175,3 +168,69 @@ impl GridDirection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinates::GridCoordinate;
+
+    #[test]
+    fn test_as_vector_is_row_column() {
+        assert_eq!(GridDirection::Up.as_vector(), GridDelta::new(-1, 0));
+        assert_eq!(GridDirection::Left.as_vector(), GridDelta::new(0, -1));
+        assert_eq!(GridDirection::DownLeft.as_vector(), GridDelta::new(1, -1));
+        assert_eq!(CompassDirection::North.as_vector(), GridDelta::new(-1, 0));
+        assert_eq!(
+            CompassDirection::SouthEast.as_vector(),
+            GridDelta::new(1, 1)
+        );
+    }
+
+    #[test]
+    fn test_compass_and_grid_vectors_match() {
+        let compass = [
+            CompassDirection::North,
+            CompassDirection::NorthWest,
+            CompassDirection::NorthEast,
+            CompassDirection::South,
+            CompassDirection::SouthWest,
+            CompassDirection::SouthEast,
+            CompassDirection::West,
+            CompassDirection::East,
+        ];
+        for direction in compass {
+            assert_eq!(
+                direction.as_vector(),
+                direction.to_grid_direction().as_vector()
+            );
+        }
+    }
+
+    #[test]
+    fn test_as_vector_matches_shifted() {
+        let coord = GridCoordinate::new(5, 5);
+        for direction in GridDirection::directions() {
+            assert_eq!(
+                Some(coord + direction.as_vector()),
+                coord.shifted(direction)
+            );
+            assert_eq!(
+                coord + direction.as_vector() + direction.opposite().as_vector(),
+                coord
+            );
+        }
+    }
+
+    #[test]
+    fn test_delta_scaling() {
+        let coord = GridCoordinate::new(5, 5);
+        assert_eq!(
+            coord + GridDirection::Right.as_vector() * 3,
+            GridCoordinate::new(5, 8)
+        );
+        assert_eq!(
+            -GridDirection::Up.as_vector(),
+            GridDirection::Down.as_vector()
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use nom::error::Error as NomError;
+use crate::parsers::{VerboseError, VerboseErrorKind};
+use nom::error::{Error as NomError, ErrorKind};
 use nom::Err as NomErr;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,16 +25,241 @@ pub enum AdventError {
     #[error("Invalid coordinate: ({row}, {col})")]
     InvalidCoordinate { row: usize, col: usize },
     #[error("Parsing error: {0}")]
-    NomError(String),
+    NomError(Box<ParseFailure>),
 }
 
-impl<I: std::fmt::Debug> From<NomErr<NomError<I>>> for AdventError {
-    fn from(err: NomErr<NomError<I>>) -> Self {
-        match err {
-            NomErr::Incomplete(_) => AdventError::NomError("Incomplete input".to_string()),
-            NomErr::Error(e) => AdventError::NomError(format!("{:?}", e)),
-            NomErr::Failure(e) => AdventError::NomError(format!("{:?}", e)),
+impl AdventError {
+    /// Work out where in `input` a nom failure happened, so it can point at the line and column
+    ///
+    /// nom only keeps the input that was left over when it gave up, so this needs the text the
+    /// parser was given. A failure from `parse_all` already knows how far into its input it got,
+    /// but one that came straight from `?` is placed by where that leftover slice sits in memory,
+    /// so it can come from parsing a single line of `input` just as well as the whole thing. That
+    /// means `input` has to be the very text that was parsed, still in memory. If the failure
+    /// didn't come from `input` at all, say from a copy that was changed first, the error is left
+    /// as it was.
+    pub fn with_input(self, input: &str) -> Self {
+        match self {
+            AdventError::NomError(failure) => {
+                AdventError::NomError(Box::new(failure.locate(input)))
+            }
+            other => other,
+        }
+    }
+
+    /// Point a failure from parsing the whole of `input` at where it happened
+    ///
+    /// nom hands back the tail of the input it was given, so how far it got is just the
+    /// difference in length, and the failure is settled there and then, never looked for again
+    /// in whatever `with_input` is later given.
+    pub(crate) fn with_whole_input(self, input: &str) -> Self {
+        match self {
+            AdventError::NomError(failure) => {
+                let mut failure = match input.len().checked_sub(failure.remaining) {
+                    Some(offset) => failure.locate_at(input, offset),
+                    None => *failure,
+                };
+                failure.address = None;
+                AdventError::NomError(Box::new(failure))
+            }
+            other => other,
+        }
+    }
+
+    /// Turn a failure from a `VerboseError` parser into an error, keeping its `context` labels
+    ///
+    /// This isn't a `From` impl because a second one would leave `?` unable to work out the error
+    /// type of a bare nom combinator, so call it with `.map_err(AdventError::from_verbose)`.
+    pub fn from_verbose<I: AsRef<str>>(err: NomErr<VerboseError<I>>) -> Self {
+        let e = match err {
+            NomErr::Incomplete(_) => {
+                return AdventError::NomError(Box::new(ParseFailure::incomplete()))
+            }
+            NomErr::Error(e) | NomErr::Failure(e) => e,
+        };
+
+        // The innermost frame is where things actually went wrong, the rest is how we got there
+        let mut expected = None;
+        let mut contexts = Vec::new();
+        for (_, kind) in &e.errors {
+            match kind {
+                VerboseErrorKind::Context(label) => contexts.push(*label),
+                VerboseErrorKind::Char(c) => {
+                    expected.get_or_insert_with(|| format!("{:?}", c));
+                }
+                VerboseErrorKind::Nom(kind) => {
+                    expected.get_or_insert_with(|| describe(*kind));
+                }
+            }
+        }
+        contexts.reverse();
+
+        let remaining = e.errors.first().map_or("", |(input, _)| input.as_ref());
+        let expected = match (expected, contexts.last()) {
+            (Some(expected), _) => expected,
+            (None, Some(label)) => label.to_string(),
+            (None, None) => "something else".to_string(),
+        };
+        AdventError::NomError(Box::new(ParseFailure::new(remaining, expected, contexts)))
+    }
+}
+
+/// Anything that might be carrying a parse failure that `with_input` can point into the input
+pub trait WithInput {
+    fn with_input(self, input: &str) -> Self;
+}
+
+impl<T> WithInput for Result<T, AdventError> {
+    fn with_input(self, input: &str) -> Self {
+        self.map_err(|e| e.with_input(input))
+    }
+}
+
+/// What nom was expecting when it gave up, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+    /// What the innermost parser wanted to see
+    pub expected: String,
+    /// The `context` labels it was inside, outermost first
+    pub contexts: Vec<&'static str>,
+    /// How many bytes of input were left when it failed
+    pub remaining: usize,
+    pub location: Option<Location>,
+    near: String,
+    /// Where the leftover input starts in memory, to find it again in the input it came from
+    ///
+    /// Only kept until the failure has been placed, as the memory can be reused once that input
+    /// is gone.
+    address: Option<usize>,
+}
+
+/// A place in the input, counting lines and columns from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub source_line: String,
+}
+
+/// How much of the leftover input to quote when the failure hasn't been located
+const NEAR_LENGTH: usize = 40;
+
+impl ParseFailure {
+    fn new(remaining: &str, expected: String, contexts: Vec<&'static str>) -> Self {
+        Self {
+            expected,
+            contexts,
+            remaining: remaining.len(),
+            location: None,
+            near: remaining.chars().take(NEAR_LENGTH).collect(),
+            address: Some(remaining.as_ptr() as usize),
+        }
+    }
+
+    fn incomplete() -> Self {
+        Self {
+            address: None,
+            ..Self::new("", "more input".to_string(), Vec::new())
+        }
+    }
+
+    fn locate(self, input: &str) -> Self {
+        let start = input.as_ptr() as usize;
+        match self.address {
+            Some(address)
+                if self.location.is_none() && (start..=start + input.len()).contains(&address) =>
+            {
+                self.locate_at(input, address - start)
+            }
+            _ => self,
+        }
+    }
+
+    fn locate_at(mut self, input: &str, offset: usize) -> Self {
+        if !input.is_char_boundary(offset) || !input[offset..].starts_with(&self.near) {
+            return self;
+        }
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        self.location = Some(Location {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            source_line: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        });
+        // Once it's been placed there's no need to go looking for it again
+        self.address = None;
+        self
+    }
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "line {}, column {}: ", location.line, location.column)?;
+        }
+        write!(f, "expected {}", self.expected)?;
+        if !self.contexts.is_empty() {
+            write!(f, " in {}", self.contexts.join(" > "))?;
         }
+
+        match &self.location {
+            Some(location) => {
+                let gutter = location.line.to_string().len();
+                write!(
+                    f,
+                    "\n{:>gutter$} | {}\n{:>gutter$} | {:>column$}",
+                    location.line,
+                    location.source_line,
+                    "",
+                    "^",
+                    column = location.column,
+                )
+            }
+            None if self.remaining == 0 => write!(f, " at the end of the input"),
+            None => write!(f, " at {:?}", self.near),
+        }
+    }
+}
+
+/// A friendlier name for what each of nom's parsers is looking for
+fn describe(kind: ErrorKind) -> String {
+    let description = match kind {
+        ErrorKind::Tag => "a tag",
+        ErrorKind::Char => "a particular character",
+        ErrorKind::OneOf => "one of the allowed characters",
+        ErrorKind::NoneOf => "a character that isn't excluded",
+        ErrorKind::Digit => "a digit",
+        ErrorKind::HexDigit => "a hex digit",
+        ErrorKind::Alpha => "a letter",
+        ErrorKind::AlphaNumeric => "a letter or digit",
+        ErrorKind::Space => "a space",
+        ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::CrLf => "a line ending",
+        ErrorKind::Eof => "the end of the input",
+        ErrorKind::Alt => "one of the alternatives",
+        ErrorKind::Many1 | ErrorKind::SeparatedList => "at least one item",
+        ErrorKind::MapRes => "a value that could be converted",
+        ErrorKind::Verify => "a value that passed verification",
+        other => return other.description().to_lowercase(),
+    };
+    description.to_string()
+}
+
+impl<I: AsRef<str>> From<NomErr<NomError<I>>> for AdventError {
+    fn from(err: NomErr<NomError<I>>) -> Self {
+        let failure = match err {
+            NomErr::Incomplete(_) => ParseFailure::incomplete(),
+            NomErr::Error(e) | NomErr::Failure(e) => {
+                ParseFailure::new(e.input.as_ref(), describe(e.code), Vec::new())
+            }
+        };
+        AdventError::NomError(Box::new(failure))
     }
 }
 
@@ -56,3 +283,132 @@ macro_rules! error {
         AdventError::Error(format!($($t)*))
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::nom_unsigned_digit;
+    use nom::character::complete::{char, digit1};
+    use nom::combinator::{eof, map_res};
+    use nom::error::context;
+    use nom::multi::separated_list1;
+    use nom::sequence::separated_pair;
+    use nom::{IResult, Parser};
+
+    fn point(input: &str) -> IResult<&str, (u32, u32)> {
+        separated_pair(nom_unsigned_digit, char(','), nom_unsigned_digit).parse(input)
+    }
+
+    fn points(input: &str) -> Result<Vec<(u32, u32)>, AdventError> {
+        let (input, points) = separated_list1(char('\n'), point).parse(input)?;
+        let (_, _) = eof(input)?;
+        Ok(points)
+    }
+
+    #[test]
+    fn test_located_failure() {
+        let input = "1,2\n3,4\n5;6";
+        let err = point(&input[8..]).map_err(AdventError::from).unwrap_err();
+        let err = err.with_input(input);
+
+        let AdventError::NomError(failure) = &err else {
+            panic!("Expected a nom error, got {:?}", err);
+        };
+        assert_eq!(
+            failure.location,
+            Some(Location {
+                line: 3,
+                column: 2,
+                source_line: "5;6".to_string(),
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: line 3, column 2: expected a particular character\n3 | 5;6\n  |  ^"
+        );
+    }
+
+    #[test]
+    fn test_located_line() {
+        // Parsing one line at a time still points at the right line, even when the same text
+        // turns up further down
+        let input = "1;2\n1;2\n1,";
+        let err = input
+            .lines()
+            .map(|line| point(line).map_err(AdventError::from))
+            .find_map(Result::err)
+            .unwrap()
+            .with_input(input);
+        assert!(err.to_string().contains("line 1, column 2"), "{}", err);
+
+        let line = input.lines().last().unwrap();
+        let err = AdventError::from(point(line).unwrap_err()).with_input(input);
+        assert!(err.to_string().contains("line 3, column 3"), "{}", err);
+    }
+
+    #[test]
+    fn test_reused_buffer() {
+        use crate::parsers::parse_all;
+
+        // A failure from `parse_all` is placed while its input is still there, and doesn't keep
+        // hold of where that input was, so text that's later given the same memory can't be
+        // mistaken for it
+        let err = {
+            let buffer = String::from("1,2\n3;4");
+            parse_all(separated_list1(char('\n'), point), &buffer).unwrap_err()
+        };
+        let AdventError::NomError(failure) = &err else {
+            panic!("Expected a nom error, got {:?}", err);
+        };
+        assert_eq!(failure.address, None);
+
+        let expected =
+            "Parsing error: line 2, column 1: expected the end of the input\n2 | 3;4\n  | ^";
+        assert_eq!(err.to_string(), expected);
+        let reused = String::from("3;4\n1,2");
+        assert_eq!(err.with_input(&reused).to_string(), expected);
+    }
+
+    #[test]
+    fn test_unlocated_failure() {
+        let err = points("1,2\nx").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: expected the end of the input at \"\\nx\""
+        );
+
+        // Text that didn't come from the failed parse is left alone
+        let err = AdventError::from(point("1;2").unwrap_err()).with_input("9,9\n1;2\n");
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: expected a particular character at \";2\""
+        );
+    }
+
+    #[test]
+    fn test_verbose_failure() {
+        fn row(input: &str) -> IResult<&str, (u32, u32), VerboseError<&str>> {
+            context(
+                "row",
+                separated_pair(
+                    context("start", nom_digit),
+                    char('-'),
+                    context("end", nom_digit),
+                ),
+            )
+            .parse(input)
+        }
+
+        fn nom_digit(input: &str) -> IResult<&str, u32, VerboseError<&str>> {
+            map_res(digit1, str::parse).parse(input)
+        }
+
+        let input = "10-20\n30-x0\n";
+        let result: Result<_, AdventError> = row(&input[6..]).map_err(AdventError::from_verbose);
+        let err = result.with_input(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: line 2, column 4: expected a digit in row > end\n2 | 30-x0\n  |    ^"
+        );
+    }
+}
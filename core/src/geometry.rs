@@ -0,0 +1,2 @@
+pub mod diamond;
+pub mod polygon;
@@ -0,0 +1,234 @@
+use crate::intervals::IntervalSet;
+use crate::prelude::*;
+use std::ops::RangeInclusive;
+
+/// Every point within a Manhattan distance of a centre point, which is a diamond on the grid
+///
+/// Turning the grid by 45° with `rotate_45` makes the diamond an axis-aligned square, which is
+/// often the easier way to think about how diamonds overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Diamond {
+    pub centre: GridCoordinate<i64>,
+    pub radius: i64,
+}
+
+impl Diamond {
+    pub fn new(centre: GridCoordinate<i64>, radius: i64) -> Self {
+        Self { centre, radius }
+    }
+
+    /// The diamond around `centre` that just reaches `edge`, like a sensor and its closest beacon
+    pub fn reaching(centre: GridCoordinate<i64>, edge: &GridCoordinate<i64>) -> Self {
+        Self::new(centre, centre.manhattan_distance(edge))
+    }
+
+    pub fn contains(&self, point: &GridCoordinate<i64>) -> bool {
+        self.centre.manhattan_distance(point) <= self.radius
+    }
+
+    /// The columns the diamond covers on a row, if it reaches the row at all
+    pub fn row_span(&self, row: i64) -> Option<RangeInclusive<i64>> {
+        let reach = self.radius - (row - self.centre.row).abs();
+        (reach >= 0).then(|| self.centre.column - reach..=self.centre.column + reach)
+    }
+
+    /// The rows the diamond covers in a column, if it reaches the column at all
+    pub fn column_span(&self, column: i64) -> Option<RangeInclusive<i64>> {
+        let reach = self.radius - (column - self.centre.column).abs();
+        (reach >= 0).then(|| self.centre.row - reach..=self.centre.row + reach)
+    }
+
+    /// The square the diamond turns into after `rotate_45`, as the ranges of `u` and `v`
+    pub fn rotated_bounds(&self) -> (RangeInclusive<i64>, RangeInclusive<i64>) {
+        let (u, v) = rotate_45(&self.centre);
+        (
+            u - self.radius..=u + self.radius,
+            v - self.radius..=v + self.radius,
+        )
+    }
+}
+
+/// Turn a point by 45° into `(u, v)`, where `u = row + column` and `v = row - column`
+///
+/// The Manhattan distance between two points becomes the larger of the differences in `u` and
+/// `v`, so diamonds become squares.
+pub fn rotate_45(point: &GridCoordinate<i64>) -> (i64, i64) {
+    (point.row + point.column, point.row - point.column)
+}
+
+/// Turn `(u, v)` back into a point, which only lands on the grid when `u` and `v` are both odd or
+/// both even
+pub fn unrotate_45(u: i64, v: i64) -> Option<GridCoordinate<i64>> {
+    ((u + v) % 2 == 0).then(|| GridCoordinate::new((u + v) / 2, (u - v) / 2))
+}
+
+/// Every column covered by at least one of the diamonds on a row
+pub fn row_coverage(diamonds: &[Diamond], row: i64) -> IntervalSet<i64> {
+    let mut coverage = IntervalSet::new();
+    for span in diamonds.iter().filter_map(|diamond| diamond.row_span(row)) {
+        coverage.insert(span);
+    }
+    coverage
+}
+
+/// Every row covered by at least one of the diamonds in a column
+pub fn column_coverage(diamonds: &[Diamond], column: i64) -> IntervalSet<i64> {
+    let mut coverage = IntervalSet::new();
+    for span in diamonds
+        .iter()
+        .filter_map(|diamond| diamond.column_span(column))
+    {
+        coverage.insert(span);
+    }
+    coverage
+}
+
+/// Find the points inside the box from `min` to `max` (inclusive) that no diamond covers
+///
+/// Rather than scanning every row, this only looks where the lines running one or two steps
+/// outside each diamond cross each other or the sides of the box. A point with covered points all
+/// around it is one step outside some diamond, and the next point along that line is covered by a
+/// diamond one or two steps away in the other direction, so the point sits on one of those
+/// crossings. When the only gap is a single point, as puzzles tend to promise, that's exactly what
+/// comes back. A bigger gap might only give up some of its points. The points are sorted.
+pub fn uncovered_points(
+    diamonds: &[Diamond],
+    min: GridCoordinate<i64>,
+    max: GridCoordinate<i64>,
+) -> Vec<GridCoordinate<i64>> {
+    let mut u_lines = Vec::new();
+    let mut v_lines = Vec::new();
+    for diamond in diamonds {
+        let (u, v) = diamond.rotated_bounds();
+        for step in 1..=2 {
+            u_lines.extend([u.start() - step, u.end() + step]);
+            v_lines.extend([v.start() - step, v.end() + step]);
+        }
+    }
+
+    let mut candidates: HashSet<GridCoordinate<i64>> = [
+        min,
+        max,
+        GridCoordinate::new(min.row, max.column),
+        GridCoordinate::new(max.row, min.column),
+    ]
+    .into_iter()
+    .collect();
+
+    for &u in &u_lines {
+        candidates.extend(v_lines.iter().filter_map(|&v| unrotate_45(u, v)));
+        // Where `row + column = u` meets the sides of the box
+        for row in [min.row, max.row] {
+            candidates.insert(GridCoordinate::new(row, u - row));
+        }
+        for column in [min.column, max.column] {
+            candidates.insert(GridCoordinate::new(u - column, column));
+        }
+    }
+    for &v in &v_lines {
+        // Where `row - column = v` meets the sides of the box
+        for row in [min.row, max.row] {
+            candidates.insert(GridCoordinate::new(row, row - v));
+        }
+        for column in [min.column, max.column] {
+            candidates.insert(GridCoordinate::new(column + v, column));
+        }
+    }
+
+    let mut uncovered: Vec<_> = candidates
+        .into_iter()
+        .filter(|point| {
+            (min.row..=max.row).contains(&point.row)
+                && (min.column..=max.column).contains(&point.column)
+        })
+        .filter(|point| !diamonds.iter().any(|diamond| diamond.contains(point)))
+        .collect();
+    uncovered.sort_by_key(|point| (point.row, point.column));
+    uncovered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans() {
+        let diamond = Diamond::reaching(GridCoordinate::new(7, 8), &GridCoordinate::new(10, 2));
+
+        assert_eq!(diamond.radius, 9);
+        assert_eq!(diamond.row_span(10), Some(2..=14));
+        assert_eq!(diamond.row_span(16), Some(8..=8));
+        assert_eq!(diamond.row_span(17), None);
+        assert_eq!(diamond.column_span(8), Some(-2..=16));
+        assert!(diamond.contains(&GridCoordinate::new(16, 8)));
+        assert!(!diamond.contains(&GridCoordinate::new(16, 9)));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let diamond = Diamond::new(GridCoordinate::new(3, -2), 4);
+        let (u, v) = diamond.rotated_bounds();
+
+        for row in -3..10 {
+            for column in -8..4 {
+                let point = GridCoordinate::new(row, column);
+                let (pu, pv) = rotate_45(&point);
+                assert_eq!(diamond.contains(&point), u.contains(&pu) && v.contains(&pv));
+                assert_eq!(unrotate_45(pu, pv), Some(point));
+            }
+        }
+        assert_eq!(unrotate_45(1, 2), None);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let diamonds = [
+            Diamond::new(GridCoordinate::new(0, 0), 2),
+            Diamond::new(GridCoordinate::new(0, 3), 1),
+            Diamond::new(GridCoordinate::new(0, 10), 1),
+        ];
+
+        let coverage = row_coverage(&diamonds, 0);
+        assert_eq!(coverage.iter().collect::<Vec<_>>(), vec![-2..=4, 9..=11]);
+        assert_eq!(row_coverage(&diamonds, 1).total_length(), 3 + 1 + 1);
+        assert_eq!(column_coverage(&diamonds, 3).total_length(), 3);
+    }
+
+    #[test]
+    fn test_uncovered_points() {
+        // Four diamonds leaving a single hole at (5, 5)
+        let diamonds = [
+            Diamond::new(GridCoordinate::new(0, 0), 9),
+            Diamond::new(GridCoordinate::new(10, 10), 9),
+            Diamond::new(GridCoordinate::new(0, 10), 9),
+            Diamond::new(GridCoordinate::new(10, 0), 9),
+        ];
+        let min = GridCoordinate::new(0, 0);
+        let max = GridCoordinate::new(10, 10);
+
+        assert_eq!(
+            uncovered_points(&diamonds, min, max),
+            vec![GridCoordinate::new(5, 5)]
+        );
+
+        // A hole in the corner of the box is found as well
+        assert_eq!(
+            uncovered_points(&diamonds[..1], min, GridCoordinate::new(5, 5)),
+            vec![GridCoordinate::new(5, 5)]
+        );
+
+        // The hole is on a line one step outside two of the diamonds, but the lines closing it in
+        // from the other direction are two steps away
+        let diamonds = [
+            Diamond::new(GridCoordinate::new(6, 5), 10),
+            Diamond::new(GridCoordinate::new(-5, -6), 10),
+            Diamond::new(GridCoordinate::new(6, -6), 10),
+            Diamond::new(GridCoordinate::new(-6, 6), 10),
+        ];
+        let (min, max) = (GridCoordinate::new(-3, -3), GridCoordinate::new(3, 3));
+        assert_eq!(
+            uncovered_points(&diamonds, min, max),
+            vec![GridCoordinate::new(0, 0)]
+        );
+    }
+}
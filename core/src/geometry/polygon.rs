@@ -0,0 +1,303 @@
+use crate::enums::DirectionShift;
+use crate::math::number_theory::gcd;
+use crate::prelude::*;
+use num_traits::PrimInt;
+
+/// How to decide if a point is inside a polygon that crosses over itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// Inside if a ray from the point crosses the boundary an odd number of times
+    EvenOdd,
+    /// Inside if the boundary winds around the point at all
+    NonZero,
+}
+
+/// A closed polygon with its corners on the integer lattice
+///
+/// The vertices are in order around the polygon, in either direction, and the last vertex
+/// connects back to the first. Apart from `contains`, everything assumes a simple polygon, that is
+/// one that doesn't cross over itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<GridCoordinate<i64>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<GridCoordinate<i64>>) -> Self {
+        let mut polygon = Self { vertices };
+        polygon.drop_closing_vertex();
+        polygon
+    }
+
+    /// Build the polygon from coordinates of any integer type, such as the `usize` coordinates of
+    /// the tiles along a loop in a grid
+    pub fn from_coordinates<T: PrimInt>(
+        vertices: impl IntoIterator<Item = GridCoordinate<T>>,
+    ) -> Result<Self> {
+        let vertices = vertices
+            .into_iter()
+            .map(|vertex| vertex.cast().ok_or(error!("Vertex does not fit in an i64")))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(vertices))
+    }
+
+    /// Build the polygon by following a list of steps from the start, like a dig plan
+    ///
+    /// Each step is a direction and how far to go in it. The steps are expected to bring us back
+    /// to the start, and if they don't the polygon is closed with a straight line.
+    pub fn from_steps<D: DirectionShift>(
+        start: GridCoordinate<i64>,
+        steps: impl IntoIterator<Item = (D, i64)>,
+    ) -> Self {
+        let mut current = start;
+        let mut vertices = vec![start];
+
+        for (direction, distance) in steps {
+            let vector = direction.to_grid_direction().as_vector();
+            current += GridDelta::new(i64::from(vector.row), i64::from(vector.column)) * distance;
+            vertices.push(current);
+        }
+
+        Self::new(vertices)
+    }
+
+    pub fn vertices(&self) -> &[GridCoordinate<i64>] {
+        &self.vertices
+    }
+
+    /// Whether every edge is either horizontal or vertical
+    pub fn is_rectilinear(&self) -> bool {
+        self.edges()
+            .all(|(a, b)| a.row == b.row || a.column == b.column)
+    }
+
+    /// Twice the enclosed area, which is always a whole number for a lattice polygon
+    ///
+    /// Calculated with the shoelace formula.
+    pub fn twice_area(&self) -> i64 {
+        self.twice_signed_area().abs()
+    }
+
+    /// The enclosed area, which can end in .5 unless the polygon is rectilinear
+    pub fn area(&self) -> f64 {
+        self.twice_area() as f64 / 2.0
+    }
+
+    /// The number of lattice points on the boundary, including the vertices
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| gcd(a.row - b.row, a.column - b.column))
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside the polygon
+    ///
+    /// Calculated with Pick's theorem, A = I + B/2 - 1, which rearranges to
+    /// I = (2A - B + 2) / 2. A polygon with no area has nothing inside it.
+    pub fn interior_points(&self) -> i64 {
+        match self.twice_area() {
+            0 => 0,
+            twice_area => (twice_area - self.boundary_points() + 2) / 2,
+        }
+    }
+
+    /// The number of lattice points inside or on the boundary
+    ///
+    /// When every lattice point stands for a whole tile, such as a trench dug around a lagoon,
+    /// this is the number of tiles covered, boundary included.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Whether a point is on one of the edges
+    pub fn on_boundary(&self, point: &GridCoordinate<i64>) -> bool {
+        self.edges().any(|(a, b)| {
+            cross(a, b, point) == 0
+                && point.row >= a.row.min(b.row)
+                && point.row <= a.row.max(b.row)
+                && point.column >= a.column.min(b.column)
+                && point.column <= a.column.max(b.column)
+        })
+    }
+
+    /// How many times the boundary goes around the point
+    ///
+    /// The sign depends on which way round the vertices are, so it's mostly useful for checking
+    /// against zero. Points on the boundary don't have a well defined winding number.
+    pub fn winding_number(&self, point: &GridCoordinate<i64>) -> i64 {
+        let mut winding = 0;
+
+        for (a, b) in self.edges() {
+            if a.row <= point.row {
+                if b.row > point.row && cross(a, b, point) > 0 {
+                    winding += 1;
+                }
+            } else if b.row <= point.row && cross(a, b, point) < 0 {
+                winding -= 1;
+            }
+        }
+
+        winding
+    }
+
+    /// Whether a point is inside the polygon, points on the boundary included
+    pub fn contains(&self, point: &GridCoordinate<i64>, rule: FillRule) -> bool {
+        if self.on_boundary(point) {
+            return true;
+        }
+
+        let winding = self.winding_number(point);
+        match rule {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+
+    fn twice_signed_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.row * b.column - a.column * b.row)
+            .sum()
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&GridCoordinate<i64>, &GridCoordinate<i64>)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Having the start repeated at the end would add an empty edge, so we drop it
+    fn drop_closing_vertex(&mut self) {
+        if self.vertices.len() > 1 && self.vertices.first() == self.vertices.last() {
+            self.vertices.pop();
+        }
+    }
+}
+
+/// Which side of the line from `a` to `b` the point is on, zero if it's on the line
+fn cross(a: &GridCoordinate<i64>, b: &GridCoordinate<i64>, point: &GridCoordinate<i64>) -> i64 {
+    (b.column - a.column) * (point.row - a.row) - (point.column - a.column) * (b.row - a.row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: i64) -> Polygon {
+        Polygon::new(vec![
+            GridCoordinate::new(0, 0),
+            GridCoordinate::new(0, size),
+            GridCoordinate::new(size, size),
+            GridCoordinate::new(size, 0),
+        ])
+    }
+
+    #[test]
+    fn test_square() {
+        let polygon = square(4);
+
+        assert!(polygon.is_rectilinear());
+        assert_eq!(polygon.twice_area(), 32);
+        assert_eq!(polygon.area(), 16.0);
+        assert_eq!(polygon.boundary_points(), 16);
+        assert_eq!(polygon.interior_points(), 9);
+        assert_eq!(polygon.lattice_points(), 25);
+    }
+
+    #[test]
+    fn test_direction_does_not_matter() {
+        let mut vertices = square(4).vertices().to_vec();
+        vertices.reverse();
+
+        assert_eq!(Polygon::new(vertices).twice_area(), 32);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let polygon = Polygon::new(vec![
+            GridCoordinate::new(0, 0),
+            GridCoordinate::new(0, 3),
+            GridCoordinate::new(3, 0),
+        ]);
+
+        assert!(!polygon.is_rectilinear());
+        assert_eq!(polygon.area(), 4.5);
+        assert_eq!(polygon.boundary_points(), 9);
+        assert_eq!(polygon.interior_points(), 1);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(Polygon::new(vec![]).interior_points(), 0);
+
+        let line = Polygon::new(vec![GridCoordinate::new(0, 0), GridCoordinate::new(0, 4)]);
+        assert_eq!(line.area(), 0.0);
+        assert_eq!(line.interior_points(), 0);
+    }
+
+    #[test]
+    fn test_from_steps() {
+        // The dig plan example from 2023 day 18
+        let steps = [
+            (GridDirection::Right, 6),
+            (GridDirection::Down, 5),
+            (GridDirection::Left, 2),
+            (GridDirection::Down, 2),
+            (GridDirection::Right, 2),
+            (GridDirection::Down, 2),
+            (GridDirection::Left, 5),
+            (GridDirection::Up, 2),
+            (GridDirection::Left, 1),
+            (GridDirection::Up, 2),
+            (GridDirection::Right, 2),
+            (GridDirection::Up, 3),
+            (GridDirection::Left, 2),
+            (GridDirection::Up, 2),
+        ];
+        let polygon = Polygon::from_steps(GridCoordinate::new(0, 0), steps);
+
+        assert_eq!(polygon.vertices().len(), 14);
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.lattice_points(), 62);
+    }
+
+    #[test]
+    fn test_from_coordinates() {
+        let polygon = Polygon::from_coordinates([
+            GridCoordinate::new(1usize, 1),
+            GridCoordinate::new(1, 3),
+            GridCoordinate::new(3, 3),
+            GridCoordinate::new(3, 1),
+            GridCoordinate::new(1, 1),
+        ])
+        .unwrap();
+
+        assert_eq!(polygon.vertices().len(), 4);
+        assert_eq!(polygon.interior_points(), 1);
+    }
+
+    #[test]
+    fn test_contains() {
+        let polygon = square(4);
+
+        assert!(polygon.contains(&GridCoordinate::new(2, 2), FillRule::EvenOdd));
+        assert!(polygon.contains(&GridCoordinate::new(0, 2), FillRule::EvenOdd));
+        assert!(polygon.on_boundary(&GridCoordinate::new(4, 4)));
+        assert!(!polygon.contains(&GridCoordinate::new(5, 2), FillRule::NonZero));
+        assert!(!polygon.contains(&GridCoordinate::new(2, -1), FillRule::NonZero));
+        assert_eq!(polygon.winding_number(&GridCoordinate::new(2, 2)).abs(), 1);
+    }
+
+    #[test]
+    fn test_fill_rules_differ_on_overlap() {
+        // Going around the same square twice winds around the inside twice
+        let mut vertices = square(4).vertices().to_vec();
+        vertices.extend(square(4).vertices().to_vec());
+        let polygon = Polygon::new(vertices);
+
+        let point = GridCoordinate::new(2, 2);
+        assert_eq!(polygon.winding_number(&point).abs(), 2);
+        assert!(polygon.contains(&point, FillRule::NonZero));
+        assert!(!polygon.contains(&point, FillRule::EvenOdd));
+    }
+}
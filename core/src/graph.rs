@@ -0,0 +1,520 @@
+use crate::prelude::*;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A graph with a node for every distinct value of `N`
+///
+/// Nodes are numbered in the order they're added, and the algorithms go through them in that
+/// order, so the results are the same from one run to the next.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    /// Sorted indices of the nodes each node has an edge to
+    adjacency: Vec<Vec<usize>>,
+    directed: bool,
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    /// An empty graph where an edge from `a` to `b` doesn't go back from `b` to `a`
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    /// An empty graph where every edge goes both ways
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    pub fn directed_from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self::directed();
+        graph.extend(edges);
+        graph
+    }
+
+    pub fn undirected_from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self::undirected();
+        graph.extend(edges);
+        graph
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            adjacency: Vec::new(),
+            directed,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Add a node without any edges, returning its index
+    ///
+    /// Adding a node that's already there just returns the index it already has.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(idx) = self.indices.get(&node) {
+            return *idx;
+        }
+        let idx = self.nodes.len();
+        self.indices.insert(node.clone(), idx);
+        self.nodes.push(node);
+        self.adjacency.push(Vec::new());
+        idx
+    }
+
+    /// Add an edge, adding either node if it isn't in the graph yet
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.link(from, to);
+        if !self.directed {
+            self.link(to, from);
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        match (self.indices.get(from), self.indices.get(to)) {
+            (Some(from), Some(to)) => self.adjacency[*from].binary_search(to).is_ok(),
+            _ => false,
+        }
+    }
+
+    /// The nodes with an edge from the given node, empty if it isn't in the graph
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = &N> + '_ {
+        self.indices
+            .get(node)
+            .map(|idx| self.adjacency[*idx].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|idx| &self.nodes[*idx])
+    }
+
+    /// Order the nodes so every edge points forwards, with Kahn's algorithm
+    ///
+    /// Nodes that could go in any order are kept in the order they were added. Fails with one of
+    /// the cycles in the message if there's no such order.
+    pub fn topological_sort(&self) -> Result<Vec<&N>>
+    where
+        N: Debug,
+    {
+        let mut in_degree = vec![0; self.nodes.len()];
+        for to in self.adjacency.iter().flatten() {
+            in_degree[*to] += 1;
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.nodes.len())
+            .filter(|idx| in_degree[*idx] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(idx) = queue.pop_front() {
+            order.push(&self.nodes[idx]);
+            for to in &self.adjacency[idx] {
+                in_degree[*to] -= 1;
+                if in_degree[*to] == 0 {
+                    queue.push_back(*to);
+                }
+            }
+        }
+
+        if order.len() < self.nodes.len() {
+            return Err(self.cycle_error());
+        }
+        Ok(order)
+    }
+
+    /// Order the nodes so every edge points forwards, with a depth first search
+    ///
+    /// Fails with the cycle it ran into in the message if there's no such order.
+    pub fn topological_sort_dfs(&self) -> Result<Vec<&N>>
+    where
+        N: Debug,
+    {
+        match self.depth_first_order() {
+            Ok(order) => Ok(order.into_iter().map(|idx| &self.nodes[idx]).collect()),
+            Err(_) => Err(self.cycle_error()),
+        }
+    }
+
+    /// A cycle in a directed graph, with the first node not repeated at the end
+    pub fn find_cycle(&self) -> Option<Vec<&N>> {
+        let cycle = self.depth_first_order().err()?;
+        Some(cycle.into_iter().map(|idx| &self.nodes[idx]).collect())
+    }
+
+    /// Split the graph into groups where every node can reach every other, with Tarjan's algorithm
+    ///
+    /// The groups come out in reverse topological order, so nothing in a group has an edge to a
+    /// group that comes after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&N>> {
+        let count = self.nodes.len();
+        let mut next_index = 0;
+        let mut indices: Vec<Option<usize>> = vec![None; count];
+        let mut low_links = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+
+        for root in 0..count {
+            if indices[root].is_some() {
+                continue;
+            }
+
+            // Each entry is a node, and how many of its edges we've been through so far
+            let mut calls = vec![(root, 0)];
+            indices[root] = Some(next_index);
+            low_links[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, edge)) = calls.last_mut() {
+                let node = *node;
+                if let Some(&next) = self.adjacency[node].get(*edge) {
+                    *edge += 1;
+                    match indices[next] {
+                        None => {
+                            indices[next] = Some(next_index);
+                            low_links[next] = next_index;
+                            next_index += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                            calls.push((next, 0));
+                        }
+                        Some(visited_index) if on_stack[next] => {
+                            low_links[node] = low_links[node].min(visited_index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                // Every edge has been followed, so hand the low link back to the caller
+                calls.pop();
+                if let Some((caller, _)) = calls.last() {
+                    low_links[*caller] = low_links[*caller].min(low_links[node]);
+                }
+
+                if Some(low_links[node]) == indices[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(&self.nodes[member]);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Every clique that can't be made any bigger, with Bron–Kerbosch and pivoting
+    ///
+    /// Only makes sense for undirected graphs.
+    pub fn maximal_cliques(&self) -> Vec<Vec<&N>> {
+        let mut cliques = Vec::new();
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            (0..self.nodes.len()).collect(),
+            Vec::new(),
+            &mut None,
+            &mut |clique| {
+                cliques.push(clique.iter().map(|idx| &self.nodes[*idx]).collect());
+                None
+            },
+        );
+        cliques
+    }
+
+    /// The biggest clique, in the order the nodes were added
+    ///
+    /// Only makes sense for undirected graphs. Stops looking down any branch of the search that
+    /// can't beat the best so far, so it's much quicker than going through `maximal_cliques`.
+    pub fn maximum_clique(&self) -> Vec<&N> {
+        let mut best: Vec<usize> = Vec::new();
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            (0..self.nodes.len()).collect(),
+            Vec::new(),
+            &mut None,
+            &mut |clique| {
+                if clique.len() > best.len() {
+                    best = clique.to_vec();
+                }
+                Some(best.len())
+            },
+        );
+
+        best.sort_unstable();
+        best.into_iter().map(|idx| &self.nodes[idx]).collect()
+    }
+
+    /// Find the maximal cliques that grow `clique` using the `candidates`, skipping anything in
+    /// `excluded` as those cliques have already been found
+    ///
+    /// `found` is called with each clique, and returns the size any more cliques have to beat, if
+    /// there is one.
+    fn bron_kerbosch(
+        &self,
+        clique: &mut Vec<usize>,
+        candidates: Vec<usize>,
+        mut excluded: Vec<usize>,
+        to_beat: &mut Option<usize>,
+        found: &mut impl FnMut(&[usize]) -> Option<usize>,
+    ) {
+        // Any maximal clique has to include the pivot or one of the nodes it isn't connected to,
+        // so picking the pivot with the most connections leaves the fewest branches
+        let Some(pivot) = candidates
+            .iter()
+            .chain(&excluded)
+            .max_by_key(|idx| intersect(&self.adjacency[**idx], &candidates).len())
+            .copied()
+        else {
+            *to_beat = found(clique);
+            return;
+        };
+
+        let mut remaining = candidates.clone();
+        for node in candidates {
+            if self.adjacency[pivot].binary_search(&node).is_ok() {
+                continue;
+            }
+            if to_beat.is_some_and(|size| clique.len() + remaining.len() <= size) {
+                return;
+            }
+
+            let neighbours = &self.adjacency[node];
+            clique.push(node);
+            self.bron_kerbosch(
+                clique,
+                intersect(neighbours, &remaining),
+                intersect(neighbours, &excluded),
+                to_beat,
+                found,
+            );
+            clique.pop();
+
+            remaining.retain(|idx| *idx != node);
+            let position = excluded.partition_point(|idx| *idx < node);
+            excluded.insert(position, node);
+        }
+    }
+
+    /// Depth first order of the nodes, or the first cycle found
+    fn depth_first_order(&self) -> std::result::Result<Vec<usize>, Vec<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            Unvisited,
+            InProgress,
+            Done,
+        }
+
+        let mut states = vec![State::Unvisited; self.nodes.len()];
+        let mut finished = Vec::with_capacity(self.nodes.len());
+
+        for root in 0..self.nodes.len() {
+            if states[root] != State::Unvisited {
+                continue;
+            }
+
+            let mut path = vec![(root, 0)];
+            states[root] = State::InProgress;
+            while let Some((node, edge)) = path.last_mut() {
+                let node = *node;
+                let Some(&next) = self.adjacency[node].get(*edge) else {
+                    states[node] = State::Done;
+                    finished.push(node);
+                    path.pop();
+                    continue;
+                };
+                *edge += 1;
+
+                match states[next] {
+                    State::Unvisited => {
+                        states[next] = State::InProgress;
+                        path.push((next, 0));
+                    }
+                    State::InProgress => {
+                        // We've come back round to something on the current path
+                        let start = path.iter().position(|(idx, _)| *idx == next).unwrap_or(0);
+                        return Err(path[start..].iter().map(|(idx, _)| *idx).collect());
+                    }
+                    State::Done => {}
+                }
+            }
+        }
+
+        finished.reverse();
+        Ok(finished)
+    }
+
+    fn cycle_error(&self) -> AdventError
+    where
+        N: Debug,
+    {
+        let cycle = self.find_cycle().unwrap_or_default();
+        error!("Graph has a cycle: {:?}", cycle)
+    }
+
+    fn link(&mut self, from: usize, to: usize) {
+        let edges = &mut self.adjacency[from];
+        if let Err(position) = edges.binary_search(&to) {
+            edges.insert(position, to);
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> Extend<(N, N)> for Graph<N> {
+    fn extend<I: IntoIterator<Item = (N, N)>>(&mut self, edges: I) {
+        for (from, to) in edges {
+            self.add_edge(from, to);
+        }
+    }
+}
+
+/// The values in both sorted lists
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let mut graph = Graph::undirected_from_edges([("a", "b"), ("b", "c"), ("a", "b")]);
+        graph.add_node("d");
+
+        assert_eq!(graph.node_count(), 4);
+        assert!(graph.has_edge(&"b", &"a"));
+        assert!(!graph.has_edge(&"a", &"c"));
+        assert_eq!(graph.neighbours(&"b").collect::<Vec<_>>(), vec![&"a", &"c"]);
+        assert_eq!(graph.neighbours(&"d").count(), 0);
+        assert_eq!(graph.neighbours(&"z").count(), 0);
+
+        let graph = Graph::directed_from_edges([("a", "b")]);
+        assert!(graph.has_edge(&"a", &"b"));
+        assert!(!graph.has_edge(&"b", &"a"));
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = Graph::directed_from_edges([(5, 11), (7, 11), (7, 8), (3, 8), (11, 2), (8, 9)]);
+
+        for order in [
+            graph.topological_sort().unwrap(),
+            graph.topological_sort_dfs().unwrap(),
+        ] {
+            assert_eq!(order.len(), 7);
+            let position = |n: i32| order.iter().position(|o| **o == n).unwrap();
+            assert!(position(5) < position(11));
+            assert!(position(7) < position(8));
+            assert!(position(11) < position(2));
+            assert!(position(8) < position(9));
+        }
+        assert_eq!(graph.find_cycle(), None);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let graph = Graph::directed_from_edges([(1, 2), (2, 3), (3, 4), (4, 2)]);
+
+        assert_eq!(graph.find_cycle(), Some(vec![&2, &3, &4]));
+        assert!(graph.topological_sort().is_err());
+        assert!(graph.topological_sort_dfs().is_err());
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = Graph::directed_from_edges([
+            ('a', 'b'),
+            ('b', 'c'),
+            ('c', 'a'),
+            ('c', 'd'),
+            ('d', 'e'),
+            ('e', 'd'),
+            ('e', 'f'),
+        ]);
+
+        let mut components: Vec<Vec<char>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut component: Vec<char> = component.into_iter().copied().collect();
+                component.sort_unstable();
+                component
+            })
+            .collect();
+
+        // Reverse topological order, so the sink comes first
+        assert_eq!(components[0], vec!['f']);
+        components.sort_unstable();
+        assert_eq!(
+            components,
+            vec![vec!['a', 'b', 'c'], vec!['d', 'e'], vec!['f']]
+        );
+    }
+
+    #[test]
+    fn test_cliques() {
+        // A square with one diagonal, and a triangle hanging off the side
+        let graph = Graph::undirected_from_edges([
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 1),
+            (1, 3),
+            (4, 5),
+            (5, 6),
+            (6, 4),
+        ]);
+
+        let mut cliques: Vec<Vec<i32>> = graph
+            .maximal_cliques()
+            .into_iter()
+            .map(|clique| {
+                let mut clique: Vec<i32> = clique.into_iter().copied().collect();
+                clique.sort_unstable();
+                clique
+            })
+            .collect();
+        cliques.sort_unstable();
+        assert_eq!(cliques, vec![vec![1, 2, 3], vec![1, 3, 4], vec![4, 5, 6]]);
+
+        let mut graph = graph;
+        graph.extend([(1, 5), (3, 5)]);
+        assert_eq!(graph.maximum_clique(), vec![&1, &3, &4, &5]);
+    }
+}
@@ -0,0 +1,330 @@
+use num_traits::PrimInt;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+mod range_map;
+
+pub use range_map::RangeMap;
+
+/// A set of integers, stored as sorted and disjoint ranges
+///
+/// Any overlapping or touching ranges are merged when inserted, so `1..=3` and `4..=6` end up as
+/// `1..=6`. Anything implementing `RangeBounds` can be used, so `1..4`, `1..=3` and even `..=3`
+/// all work, and the set always hands back inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    /// Inclusive (start, end) pairs, sorted and with at least one missing value between each
+    intervals: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of separate ranges in the set
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// The number of values covered by the set
+    ///
+    /// Note: This overflows if the set covers every value `T` can hold
+    pub fn total_length(&self) -> T {
+        self.intervals.iter().fold(T::zero(), |acc, (start, end)| {
+            acc + (*end - *start) + T::one()
+        })
+    }
+
+    /// The smallest and largest value in the set
+    pub fn span(&self) -> Option<RangeInclusive<T>> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+        Some(first.0..=last.1)
+    }
+
+    /// Iterate over the ranges in the set, in order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.iter().map(|(start, end)| *start..=*end)
+    }
+
+    /// Iterate over the gaps between the ranges in the set, in order
+    ///
+    /// Anything before the first range or after the last one is not a gap, use `complement` to
+    /// include those as well.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|pair| (pair[0].1 + T::one())..=(pair[1].0 - T::one()))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.intervals.partition_point(|(_, end)| *end < value);
+        self.intervals
+            .get(idx)
+            .is_some_and(|(start, _)| *start <= value)
+    }
+
+    /// Whether the whole range is in the set, which is always true for an empty range
+    pub fn contains_range<R: RangeBounds<T>>(&self, range: R) -> bool {
+        let Some((start, end)) = to_inclusive(&range) else {
+            return true;
+        };
+        let idx = self
+            .intervals
+            .partition_point(|(_, iv_end)| *iv_end < start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|(iv_start, iv_end)| *iv_start <= start && end <= *iv_end)
+    }
+
+    /// Whether any part of the range is in the set
+    pub fn overlaps<R: RangeBounds<T>>(&self, range: R) -> bool {
+        let Some((start, end)) = to_inclusive(&range) else {
+            return false;
+        };
+        let idx = self
+            .intervals
+            .partition_point(|(_, iv_end)| *iv_end < start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|(iv_start, _)| *iv_start <= end)
+    }
+
+    pub fn insert<R: RangeBounds<T>>(&mut self, range: R) {
+        let Some((start, end)) = to_inclusive(&range) else {
+            return;
+        };
+
+        // Everything from `lo` up to `hi` either overlaps or touches the new range
+        let lo = self
+            .intervals
+            .partition_point(|(_, iv_end)| iv_end.saturating_add(T::one()) < start);
+        let hi = self
+            .intervals
+            .partition_point(|(iv_start, _)| *iv_start <= end.saturating_add(T::one()));
+
+        let merged = if lo < hi {
+            (
+                start.min(self.intervals[lo].0),
+                end.max(self.intervals[hi - 1].1),
+            )
+        } else {
+            (start, end)
+        };
+        self.intervals.splice(lo..hi, [merged]);
+    }
+
+    pub fn remove<R: RangeBounds<T>>(&mut self, range: R) {
+        let Some((start, end)) = to_inclusive(&range) else {
+            return;
+        };
+
+        // Everything from `lo` up to `hi` overlaps the removed range
+        let lo = self
+            .intervals
+            .partition_point(|(_, iv_end)| *iv_end < start);
+        let hi = self
+            .intervals
+            .partition_point(|(iv_start, _)| *iv_start <= end);
+        if lo >= hi {
+            return;
+        }
+
+        let mut remaining = Vec::with_capacity(2);
+        let (first_start, _) = self.intervals[lo];
+        let (_, last_end) = self.intervals[hi - 1];
+        if first_start < start {
+            remaining.push((first_start, start - T::one()));
+        }
+        if last_end > end {
+            remaining.push((end + T::one(), last_end));
+        }
+        self.intervals.splice(lo..hi, remaining);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for (start, end) in &other.intervals {
+            result.insert(*start..=*end);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (a_start, a_end) = self.intervals[a];
+            let (b_start, b_end) = other.intervals[b];
+
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                intervals.push((start, end));
+            }
+
+            // Move past whichever range ends first, as it can't overlap anything else
+            if a_end < b_end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for (start, end) in &other.intervals {
+            result.remove(*start..=*end);
+        }
+        result
+    }
+
+    /// Everything within the bounds that is not in the set
+    pub fn complement<R: RangeBounds<T>>(&self, bounds: R) -> Self {
+        let mut result = Self::new();
+        result.insert(bounds);
+        result.difference(self)
+    }
+}
+
+impl<T: PrimInt, R: RangeBounds<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: PrimInt, R: RangeBounds<T>> Extend<R> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = R>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+/// Turn any range into inclusive (start, end) bounds, or `None` if the range is empty
+fn to_inclusive<T: PrimInt, R: RangeBounds<T>>(range: &R) -> Option<(T, T)> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(&T::one())?,
+        Bound::Unbounded => T::min_value(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => *end,
+        Bound::Excluded(end) => end.checked_sub(&T::one())?,
+        Bound::Unbounded => T::max_value(),
+    };
+
+    (start <= end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges<T: PrimInt>(set: &IntervalSet<T>) -> Vec<RangeInclusive<T>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::new();
+        set.insert(10..=14);
+        set.insert(3..=5);
+        set.insert(16..=20);
+        set.insert(12..=18);
+
+        assert_eq!(ranges(&set), vec![3..=5, 10..=20]);
+
+        // Touching ranges are merged as well
+        set.insert(6..=9);
+        assert_eq!(ranges(&set), vec![3..=20]);
+    }
+
+    #[test]
+    fn test_half_open_and_inclusive() {
+        let set: IntervalSet<u32> = [1..4, 10..12].into_iter().collect();
+        let other: IntervalSet<u32> = [1..=3, 10..=11].into_iter().collect();
+
+        assert_eq!(set, other);
+        assert_eq!(set.total_length(), 5);
+
+        let mut empty: IntervalSet<u32> = IntervalSet::new();
+        empty.insert(5..5);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: IntervalSet<i32> = [-10..=10].into_iter().collect();
+        set.remove(-2..3);
+        assert_eq!(ranges(&set), vec![-10..=-3, 3..=10]);
+
+        set.remove(..=-5);
+        assert_eq!(ranges(&set), vec![-4..=-3, 3..=10]);
+
+        set.remove(-3..=3);
+        assert_eq!(ranges(&set), vec![-4..=-4, 4..=10]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: IntervalSet<u64> = [3..=5, 10..=14].into_iter().collect();
+
+        assert!(set.contains(3));
+        assert!(set.contains(12));
+        assert!(!set.contains(2));
+        assert!(!set.contains(7));
+        assert!(!set.contains(15));
+
+        assert!(set.contains_range(10..15));
+        assert!(!set.contains_range(4..=10));
+        assert!(set.overlaps(4..=10));
+        assert!(!set.overlaps(6..10));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<i64> = [0..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<i64> = [5..=25].into_iter().collect();
+
+        assert_eq!(ranges(&a.union(&b)), vec![0..=30]);
+        assert_eq!(ranges(&a.intersection(&b)), vec![5..=10, 20..=25]);
+        assert_eq!(ranges(&a.difference(&b)), vec![0..=4, 26..=30]);
+        assert_eq!(ranges(&b.difference(&a)), vec![11..=19]);
+    }
+
+    #[test]
+    fn test_complement_and_gaps() {
+        let set: IntervalSet<i32> = [3..=5, 8..=9, 15..=20].into_iter().collect();
+
+        assert_eq!(ranges(&set.complement(0..=17)), vec![0..=2, 6..=7, 10..=14]);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![6..=7, 10..=14]);
+        assert_eq!(set.span(), Some(3..=20));
+    }
+
+    #[test]
+    fn test_extreme_values() {
+        let mut set: IntervalSet<u8> = IntervalSet::new();
+        set.insert(250..=255);
+        set.insert(0..=5);
+        set.insert(6..);
+
+        assert_eq!(ranges(&set), vec![0..=255]);
+        assert!(set.complement(..).is_empty());
+
+        set.remove(255..);
+        assert_eq!(set.total_length(), 255);
+    }
+}
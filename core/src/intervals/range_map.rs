@@ -0,0 +1,366 @@
+use super::{to_inclusive, IntervalSet};
+use crate::prelude::*;
+use num_traits::{PrimInt, WrappingAdd, WrappingSub};
+use std::hash::{Hash, Hasher};
+use std::ops::{RangeBounds, RangeInclusive};
+
+/// A piecewise function that shifts ranges of integers, like the maps in an almanac
+///
+/// Each piece takes a source range and moves it so it starts at a destination. Anything not
+/// covered by a piece maps to itself, so an empty map is the identity.
+///
+/// Two maps are equal when they move the same values to the same places, however they were built.
+#[derive(Debug, Clone, Default)]
+pub struct RangeMap<T> {
+    /// Sorted and disjoint, with adjacent pieces that shift by the same amount merged together
+    pieces: Vec<Piece<T>>,
+    /// Source ranges that were inserted mapping onto themselves, only kept so that later inserts
+    /// can't overlap them
+    fixed: IntervalSet<T>,
+}
+
+impl<T: PartialEq> PartialEq for RangeMap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pieces == other.pieces
+    }
+}
+
+impl<T: Eq> Eq for RangeMap<T> {}
+
+impl<T: Hash> Hash for RangeMap<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pieces.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Piece<T> {
+    start: T,
+    end: T,
+    destination: T,
+}
+
+impl<T: PrimInt + WrappingAdd + WrappingSub> RangeMap<T> {
+    pub fn new() -> Self {
+        Self {
+            pieces: Vec::new(),
+            fixed: IntervalSet::new(),
+        }
+    }
+
+    /// Map the source range so that it starts at `destination`
+    ///
+    /// Fails if the source overlaps a piece that is already in the map, or the destination range
+    /// would run past the largest value `T` can hold.
+    pub fn insert<R: RangeBounds<T>>(&mut self, source: R, destination: T) -> Result<()> {
+        let Some((start, end)) = to_inclusive(&source) else {
+            return Ok(());
+        };
+        if destination.wrapping_add(&end.wrapping_sub(&start)) < destination {
+            return Err(error!("Destination of a mapped range overflows"));
+        }
+
+        let idx = self.pieces.partition_point(|piece| piece.end < start);
+        if self.pieces.get(idx).is_some_and(|piece| piece.start <= end)
+            || self.fixed.overlaps(start..=end)
+        {
+            return Err(error!("Mapped ranges overlap"));
+        }
+
+        if destination == start {
+            self.fixed.insert(start..=end);
+        } else {
+            self.pieces.insert(
+                idx,
+                Piece {
+                    start,
+                    end,
+                    destination,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Iterate over the source ranges that are moved and where each of them starts afterwards
+    pub fn iter(&self) -> impl Iterator<Item = (RangeInclusive<T>, T)> + '_ {
+        self.pieces
+            .iter()
+            .map(|piece| (piece.start..=piece.end, piece.destination))
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let idx = self.pieces.partition_point(|piece| piece.end < value);
+        match self.pieces.get(idx) {
+            Some(piece) if piece.start <= value => piece.map(value),
+            _ => value,
+        }
+    }
+
+    /// Map every value in the range, which can end up split across several ranges
+    pub fn map_range<R: RangeBounds<T>>(&self, range: R) -> IntervalSet<T> {
+        let mut result = IntervalSet::new();
+        let Some((start, end)) = to_inclusive(&range) else {
+            return result;
+        };
+
+        for segment in self.segments() {
+            if segment.end < start || segment.start > end {
+                continue;
+            }
+            let low = start.max(segment.start);
+            let high = end.min(segment.end);
+            result.insert(segment.map(low)..=segment.map(high));
+        }
+
+        result
+    }
+
+    /// Map every value in the set
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter().fold(IntervalSet::new(), |acc, range| {
+            acc.union(&self.map_range(range))
+        })
+    }
+
+    /// A single map that does the same as applying `self` and then `next`
+    ///
+    /// Chaining a whole almanac into one map means each seed range only needs to be mapped once.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut result = Self::new();
+
+        for segment in self.segments() {
+            let output_start = segment.destination;
+            let output_end = segment.map(segment.end);
+
+            for next_segment in next.segments() {
+                if next_segment.end < output_start || next_segment.start > output_end {
+                    continue;
+                }
+                let low = output_start.max(next_segment.start);
+                let high = output_end.min(next_segment.end);
+                result.push(Piece {
+                    start: segment.start.wrapping_add(&low.wrapping_sub(&output_start)),
+                    end: segment
+                        .start
+                        .wrapping_add(&high.wrapping_sub(&output_start)),
+                    destination: next_segment.map(low),
+                });
+            }
+        }
+
+        result
+    }
+
+    /// The map that undoes this one
+    ///
+    /// Only possible when no two values map to the same place, which means the pieces have to be
+    /// moved around within the ranges they cover between them.
+    pub fn invert(&self) -> Result<Self> {
+        let mut inverted: Vec<_> = self
+            .pieces
+            .iter()
+            .map(|piece| Piece {
+                start: piece.destination,
+                end: piece.map(piece.end),
+                destination: piece.start,
+            })
+            .collect();
+        inverted.sort_unstable_by_key(|piece| piece.start);
+
+        if inverted.windows(2).any(|pair| pair[0].end >= pair[1].start) {
+            return Err(error!("Map is not invertible, mapped ranges overlap"));
+        }
+        let sources: IntervalSet<T> = self.iter().map(|(range, _)| range).collect();
+        let destinations: IntervalSet<T> = inverted
+            .iter()
+            .map(|piece| piece.start..=piece.end)
+            .collect();
+        if sources != destinations {
+            return Err(error!(
+                "Map is not invertible, mapped ranges overlap unmapped values"
+            ));
+        }
+
+        let mut result = Self::new();
+        for piece in inverted {
+            result.push(piece);
+        }
+        Ok(result)
+    }
+
+    /// Every value split into pieces, including the unmapped gaps as pieces that don't move
+    fn segments(&self) -> Vec<Piece<T>> {
+        let mut segments = Vec::with_capacity(self.pieces.len() * 2 + 1);
+        let mut next = Some(T::min_value());
+
+        for piece in &self.pieces {
+            if let Some(gap_start) = next.filter(|gap_start| *gap_start < piece.start) {
+                segments.push(Piece::identity(gap_start, piece.start - T::one()));
+            }
+            segments.push(*piece);
+            next = piece.end.checked_add(&T::one());
+        }
+        if let Some(gap_start) = next {
+            segments.push(Piece::identity(gap_start, T::max_value()));
+        }
+
+        segments
+    }
+
+    /// Add a piece after all the others, merging it with the last one if they line up
+    fn push(&mut self, piece: Piece<T>) {
+        if piece.destination == piece.start {
+            return;
+        }
+        if let Some(last) = self.pieces.last_mut() {
+            // Nothing can follow a piece that ends at or maps onto the largest value
+            if last.end.checked_add(&T::one()) == Some(piece.start)
+                && last.map(last.end).checked_add(&T::one()) == Some(piece.destination)
+            {
+                last.end = piece.end;
+                return;
+            }
+        }
+        self.pieces.push(piece);
+    }
+}
+
+impl<T: PrimInt + WrappingAdd + WrappingSub> Piece<T> {
+    fn identity(start: T, end: T) -> Self {
+        Self {
+            start,
+            end,
+            destination: start,
+        }
+    }
+
+    /// Where a value within the piece ends up
+    ///
+    /// This wraps, as the distance from the start can be too big for a signed `T` even when the
+    /// value it's mapped to fits fine.
+    fn map(&self, value: T) -> T {
+        self.destination
+            .wrapping_add(&value.wrapping_sub(&self.start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The seed-to-soil map from 2023 day 5
+    fn seed_to_soil() -> RangeMap<u64> {
+        let mut map = RangeMap::new();
+        map.insert(98..100, 50).unwrap();
+        map.insert(50..98, 52).unwrap();
+        map
+    }
+
+    #[test]
+    fn test_map() {
+        let map = seed_to_soil();
+
+        assert_eq!(map.map(0), 0);
+        assert_eq!(map.map(49), 49);
+        assert_eq!(map.map(50), 52);
+        assert_eq!(map.map(97), 99);
+        assert_eq!(map.map(98), 50);
+        assert_eq!(map.map(100), 100);
+    }
+
+    #[test]
+    fn test_insert_rejects_overlap() {
+        let mut map = seed_to_soil();
+
+        assert!(map.insert(90..=98, 0).is_err());
+        assert!(map.insert(100..=110, 0).is_ok());
+        assert!(RangeMap::new().insert(10u8..=20, 250).is_err());
+        assert!(RangeMap::new().insert(-100i8..=100, -50).is_err());
+        assert!(RangeMap::new().insert(-100i8..=100, -128).is_ok());
+
+        // Ranges that map onto themselves still count
+        let mut map = RangeMap::new();
+        map.insert(10..20, 10).unwrap();
+        assert!(map.insert(15..25, 0).is_err());
+        assert!(map.insert(20..25, 0).is_ok());
+        assert_eq!(map.map(12), 12);
+    }
+
+    #[test]
+    fn test_map_range() {
+        let map = seed_to_soil();
+        let mapped: Vec<_> = map.map_range(45..=99).iter().collect();
+
+        // 45..=49 stays put, 50..=97 moves up two and 98..=99 fills the gap left behind
+        assert_eq!(mapped, vec![45..=99]);
+
+        let mapped: Vec<_> = map.map_range(96..=101).iter().collect();
+        assert_eq!(mapped, vec![50..=51, 98..=101]);
+    }
+
+    #[test]
+    fn test_then() {
+        let first = seed_to_soil();
+        let mut second = RangeMap::new();
+        second.insert(15..52, 0).unwrap();
+        second.insert(52..54, 37).unwrap();
+        second.insert(0..15, 39).unwrap();
+
+        let composed = first.then(&second);
+        for value in 0..=120 {
+            assert_eq!(composed.map(value), second.map(first.map(value)));
+        }
+
+        let set: IntervalSet<u64> = [10..=20, 60..=99].into_iter().collect();
+        assert_eq!(composed.map_set(&set), second.map_set(&first.map_set(&set)));
+    }
+
+    #[test]
+    fn test_then_merges_pieces() {
+        let mut first = RangeMap::new();
+        first.insert(0..10, 100).unwrap();
+        let mut second = RangeMap::new();
+        second.insert(100..=104, 0).unwrap();
+        second.insert(105..=109, 5).unwrap();
+
+        // 0..10 goes out and comes straight back, so it drops out, and the two halves of
+        // 100..110 that aren't touched by `first` are both shifted by the same amount
+        let composed = first.then(&second);
+        assert_eq!(composed.iter().collect::<Vec<_>>(), vec![(100..=109, 0)]);
+    }
+
+    #[test]
+    fn test_then_up_to_the_largest_value() {
+        let mut first = RangeMap::new();
+        first.insert(0u8..=5, u8::MAX - 5).unwrap();
+        first.insert(6..=7, 0).unwrap();
+
+        let composed = first.then(&RangeMap::new());
+        for value in 0..=u8::MAX {
+            assert_eq!(composed.map(value), first.map(value));
+        }
+    }
+
+    #[test]
+    fn test_invert() {
+        let map = seed_to_soil();
+        let inverse = map.invert().unwrap();
+
+        for value in 0..=120 {
+            assert_eq!(inverse.map(map.map(value)), value);
+        }
+        assert_eq!(inverse.invert().unwrap(), map);
+
+        // Sending two ranges to the same place can't be undone
+        let mut map = RangeMap::new();
+        map.insert(0..10, 20).unwrap();
+        map.insert(10..20, 20).unwrap();
+        assert!(map.invert().is_err());
+
+        // Neither can moving a range on top of values that stay put
+        let mut map = RangeMap::new();
+        map.insert(0..10, 5).unwrap();
+        assert!(map.invert().is_err());
+    }
+}
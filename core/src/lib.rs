@@ -1,9 +1,22 @@
 #[macro_use]
 pub mod error_handling;
+pub mod automaton;
 pub mod benchmark;
+pub mod bitset;
 pub mod coordinates;
+pub mod cycle;
 pub mod enums;
+pub mod geometry;
+pub mod graph;
 pub mod input;
+pub mod intervals;
+pub mod math;
+pub mod memo;
+pub mod ocr;
 pub mod parsers;
 pub mod prelude;
+pub mod regions;
+pub mod search;
+pub mod symmetry;
 pub mod timeouts;
+pub mod trie;
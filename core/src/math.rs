@@ -0,0 +1,2 @@
+pub mod linear;
+pub mod number_theory;
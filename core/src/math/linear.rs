@@ -0,0 +1,695 @@
+use crate::math::number_theory::{crt, gcd, lcm_all, mod_inverse};
+use crate::prelude::*;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An exact fraction, always stored in its lowest terms with a positive denominator
+///
+/// Everything is done in `i128`, which is plenty for the sizes of numbers puzzles throw at us, so
+/// arithmetic panics on overflow like the built in integers rather than quietly rounding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Panics if the denominator is zero
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "Rational with a zero denominator");
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value as a whole number, or `None` if it's a fraction
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    /// The largest whole number that isn't more than the value
+    pub fn floor(&self) -> i128 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    /// The smallest whole number that isn't less than the value
+    pub fn ceil(&self) -> i128 {
+        -(-*self).floor()
+    }
+
+    /// Panics if the value is zero
+    pub fn recip(&self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+macro_rules! rational_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Rational {
+                fn from(value: $t) -> Self {
+                    Self {
+                        numerator: value as i128,
+                        denominator: 1,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+rational_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.numerator * other.denominator + other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // Cancel across first, to keep the intermediate values as small as we can
+        let a = gcd(self.numerator, other.denominator).max(1);
+        let b = gcd(other.numerator, self.denominator).max(1);
+        Self::new(
+            (self.numerator / a) * (other.numerator / b),
+            (self.denominator / b) * (other.denominator / a),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// Panics when dividing by zero
+    fn div(self, other: Self) -> Self {
+        Mul::mul(self, other.recip())
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Both denominators are positive, so cross multiplying keeps the order
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// Every solution to a linear system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    /// The equations contradict each other
+    None,
+    /// Exactly one value for each unknown
+    Unique(Vec<Rational>),
+    /// Any `particular` plus any multiples of each of the `directions`
+    ///
+    /// There is one direction for each unknown that can be picked freely.
+    Infinite {
+        particular: Vec<Rational>,
+        directions: Vec<Vec<Rational>>,
+    },
+}
+
+/// The most combinations of free unknowns `non_negative_integer_solutions` will try
+const MAX_COMBINATIONS: i128 = 10_000_000;
+
+/// A set of linear equations, `coefficients * unknowns = constants`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearSystem {
+    coefficients: Vec<Vec<Rational>>,
+    constants: Vec<Rational>,
+    unknowns: usize,
+}
+
+impl LinearSystem {
+    /// Build the system from one row of coefficients per equation, along with what each equation
+    /// is equal to
+    pub fn new<T: Into<Rational>>(coefficients: Vec<Vec<T>>, constants: Vec<T>) -> Result<Self> {
+        if coefficients.len() != constants.len() {
+            return Err(error!(
+                "{} rows of coefficients but {} constants",
+                coefficients.len(),
+                constants.len()
+            ));
+        }
+        let unknowns = coefficients.first().map_or(0, |row| row.len());
+        if coefficients.iter().any(|row| row.len() != unknowns) {
+            return Err(error!("Every equation needs the same number of unknowns"));
+        }
+
+        Ok(Self {
+            coefficients: coefficients
+                .into_iter()
+                .map(|row| row.into_iter().map(Into::into).collect())
+                .collect(),
+            constants: constants.into_iter().map(Into::into).collect(),
+            unknowns,
+        })
+    }
+
+    pub fn unknowns(&self) -> usize {
+        self.unknowns
+    }
+
+    /// Solve the system exactly with Gaussian elimination
+    pub fn solve(&self) -> Solution {
+        let Some(reduced) = self.reduce() else {
+            return Solution::None;
+        };
+
+        let particular = reduced.particular();
+        if reduced.free.is_empty() {
+            Solution::Unique(particular)
+        } else {
+            let directions = reduced
+                .free
+                .iter()
+                .map(|free| reduced.direction(*free))
+                .collect();
+            Solution::Infinite {
+                particular,
+                directions,
+            }
+        }
+    }
+
+    /// Every solution where the unknowns are all whole numbers of zero or more
+    ///
+    /// This is the one for "how many times do we press each button" style puzzles. When there are
+    /// infinitely many solutions, each free unknown has to be kept in check by an equation with no
+    /// negative coefficients, otherwise there could be infinitely many answers and this fails. It
+    /// also fails if that leaves too many combinations of free unknowns to try them all, in which
+    /// case `cheapest_non_negative_integer_solution` may still be able to help.
+    pub fn non_negative_integer_solutions(&self) -> Result<Vec<Vec<i128>>> {
+        let Some(reduced) = self.reduce() else {
+            return Ok(Vec::new());
+        };
+
+        let bounds = reduced
+            .free
+            .iter()
+            .map(|free| {
+                self.upper_bound(*free).ok_or(error!(
+                    "Unknown {} can grow without limit, so there may be infinitely many solutions",
+                    free
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let combinations = bounds
+            .iter()
+            .try_fold(1i128, |acc, bound| acc.checked_mul(bound + 1))
+            .filter(|combinations| *combinations <= MAX_COMBINATIONS);
+        if combinations.is_none() {
+            return Err(error!(
+                "Too many combinations of the free unknowns to try every one"
+            ));
+        }
+
+        let particular = reduced.particular();
+        let directions: Vec<_> = reduced
+            .free
+            .iter()
+            .map(|free| reduced.direction(*free))
+            .collect();
+
+        // Try every combination of values for the free unknowns, counting up like an odometer
+        let mut solutions = Vec::new();
+        let mut free_values = vec![0; bounds.len()];
+        loop {
+            let solution = (0..self.unknowns).map(|idx| {
+                directions
+                    .iter()
+                    .zip(&free_values)
+                    .fold(particular[idx], |acc, (direction, value)| {
+                        acc + direction[idx] * Rational::from(*value)
+                    })
+                    .to_integer()
+                    .filter(|value| *value >= 0)
+            });
+            if let Some(solution) = solution.collect::<Option<Vec<_>>>() {
+                solutions.push(solution);
+            }
+
+            let Some(idx) = free_values
+                .iter()
+                .zip(&bounds)
+                .position(|(value, bound)| value < bound)
+            else {
+                break;
+            };
+            free_values[idx] += 1;
+            free_values[..idx].fill(0);
+        }
+
+        Ok(solutions)
+    }
+
+    /// The solution in whole numbers of zero or more with the lowest total cost, where each unknown
+    /// costs the matching amount in `costs`
+    ///
+    /// With a single free unknown this doesn't try every value of it. The solutions are evenly
+    /// spaced along a line, and as the cost goes up steadily along it, the cheapest is at one end
+    /// of the stretch where every unknown is zero or more. Fails if the cost can keep going down
+    /// forever, or there are more free unknowns than `non_negative_integer_solutions` can manage.
+    pub fn cheapest_non_negative_integer_solution(
+        &self,
+        costs: &[i128],
+    ) -> Result<Option<Vec<i128>>> {
+        if costs.len() != self.unknowns {
+            return Err(error!(
+                "{} costs for {} unknowns",
+                costs.len(),
+                self.unknowns
+            ));
+        }
+        let cost =
+            |solution: &[i128]| -> i128 { solution.iter().zip(costs).map(|(v, c)| v * c).sum() };
+
+        let Some(reduced) = self.reduce() else {
+            return Ok(None);
+        };
+        if reduced.free.len() != 1 {
+            let solutions = self.non_negative_integer_solutions()?;
+            return Ok(solutions.into_iter().min_by_key(|solution| cost(solution)));
+        }
+
+        let particular = reduced.particular();
+        let direction = reduced.direction(reduced.free[0]);
+        let Some(steps) = integer_steps(&particular, &direction) else {
+            return Ok(None);
+        };
+
+        // Every unknown has to stay at zero or more, which bounds how far along we can go. The
+        // free unknown is just the number of steps, so that's at least zero too.
+        let mut lowest = 0;
+        let mut highest = None;
+        for (start, step) in particular.iter().zip(&direction) {
+            match step.numerator().signum() {
+                1 => lowest = lowest.max((-*start / *step).ceil()),
+                -1 => {
+                    let limit = (*start / -*step).floor();
+                    highest = Some(highest.map_or(limit, |highest: i128| highest.min(limit)));
+                }
+                _ if *start < Rational::ZERO => return Ok(None),
+                _ => {}
+            }
+        }
+
+        let (offset, spacing) = steps;
+        let first = lowest + (offset - lowest).rem_euclid(spacing);
+        let last = highest.map(|highest| highest - (highest - offset).rem_euclid(spacing));
+        if last.is_some_and(|last| last < first) {
+            return Ok(None);
+        }
+
+        let slope = direction
+            .iter()
+            .zip(costs)
+            .fold(Rational::ZERO, |acc, (step, c)| {
+                acc + *step * Rational::from(*c)
+            });
+        let best = match (slope < Rational::ZERO, last) {
+            (false, _) => first,
+            (true, Some(last)) => last,
+            (true, None) => {
+                return Err(error!(
+                    "The cost keeps going down without limit, so there's no cheapest solution"
+                ))
+            }
+        };
+
+        let solution = particular
+            .iter()
+            .zip(&direction)
+            .map(|(start, step)| *start + *step * Rational::from(best))
+            .map(|value| value.to_integer())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(error!("Solution should have been whole numbers"))?;
+        Ok(Some(solution))
+    }
+
+    /// The largest a non-negative unknown could be, if any equation keeps it in check
+    ///
+    /// An equation with no negative coefficients and a positive one for the unknown means the
+    /// unknown can't be more than the constant divided by that coefficient.
+    fn upper_bound(&self, unknown: usize) -> Option<i128> {
+        self.coefficients
+            .iter()
+            .zip(&self.constants)
+            .filter(|(row, _)| row.iter().all(|c| *c >= Rational::ZERO))
+            .filter(|(row, _)| row[unknown] > Rational::ZERO)
+            .map(|(row, constant)| {
+                let bound = *constant / row[unknown];
+                bound.numerator().div_euclid(bound.denominator())
+            })
+            .min()
+    }
+
+    /// Reduced row echelon form, or `None` if the equations are inconsistent
+    fn reduce(&self) -> Option<Reduced> {
+        let mut rows: Vec<Vec<Rational>> = self
+            .coefficients
+            .iter()
+            .zip(&self.constants)
+            .map(|(row, constant)| row.iter().copied().chain([*constant]).collect())
+            .collect();
+
+        let mut pivots = Vec::new();
+        let mut free = Vec::new();
+        for col in 0..self.unknowns {
+            let row = pivots.len();
+            let Some(pivot) = (row..rows.len()).find(|r| !rows[*r][col].is_zero()) else {
+                free.push(col);
+                continue;
+            };
+            rows.swap(row, pivot);
+
+            let scale = rows[row][col].recip();
+            for value in rows[row].iter_mut() {
+                *value = *value * scale;
+            }
+
+            let pivot_row = rows[row].clone();
+            for (idx, other) in rows.iter_mut().enumerate() {
+                let factor = other[col];
+                if idx == row || factor.is_zero() {
+                    continue;
+                }
+                for (value, pivot_value) in other.iter_mut().zip(&pivot_row).skip(col) {
+                    *value = *value - *pivot_value * factor;
+                }
+            }
+            pivots.push(col);
+        }
+
+        // Anything left over has no unknowns, so it had better be equal to zero
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| !row[self.unknowns].is_zero())
+        {
+            return None;
+        }
+
+        rows.truncate(pivots.len());
+        Some(Reduced {
+            rows,
+            pivots,
+            free,
+            unknowns: self.unknowns,
+        })
+    }
+}
+
+/// The numbers of steps along `direction` from `start` that land on whole numbers, as an offset and
+/// the spacing between them, or `None` if there aren't any
+fn integer_steps(start: &[Rational], direction: &[Rational]) -> Option<(i128, i128)> {
+    // Scaling everything up by all the denominators makes every value `start + t * step` whole
+    // exactly when `scaled_start + t * scaled_step` is a multiple of the scale
+    let denominators: Vec<i128> = start
+        .iter()
+        .chain(direction)
+        .map(|value| value.denominator())
+        .collect();
+    let scale = lcm_all(&denominators)?;
+
+    let mut congruences = Vec::new();
+    for (start, step) in start.iter().zip(direction) {
+        let scaled_start = (*start * Rational::from(scale)).to_integer()?;
+        let scaled_step = (*step * Rational::from(scale)).to_integer()?;
+
+        // Solve `scaled_step * t == -scaled_start` modulo the scale
+        let g = gcd(scaled_step, scale);
+        if scaled_start % g != 0 {
+            return None;
+        }
+        let modulus = scale / g;
+        let inverse = mod_inverse(scaled_step / g, modulus)?;
+        congruences.push((
+            (-scaled_start / g).rem_euclid(modulus) * inverse % modulus,
+            modulus,
+        ));
+    }
+
+    // Every modulus divides the scale, so the only way this can fail is if the congruences
+    // disagree, and then no number of steps works
+    crt(&congruences).ok()
+}
+
+/// A system in reduced row echelon form
+struct Reduced {
+    /// Only the rows with a pivot, the last value in each is the constant
+    rows: Vec<Vec<Rational>>,
+    /// The column of the pivot in each row
+    pivots: Vec<usize>,
+    /// The unknowns that don't have a pivot, so can take any value
+    free: Vec<usize>,
+    unknowns: usize,
+}
+
+impl Reduced {
+    /// The solution with every free unknown set to zero
+    fn particular(&self) -> Vec<Rational> {
+        let mut solution = vec![Rational::ZERO; self.unknowns];
+        for (row, pivot) in self.rows.iter().zip(&self.pivots) {
+            solution[*pivot] = row[self.unknowns];
+        }
+        solution
+    }
+
+    /// How the solution changes when the free unknown goes up by one
+    fn direction(&self, free: usize) -> Vec<Rational> {
+        let mut direction = vec![Rational::ZERO; self.unknowns];
+        direction[free] = Rational::ONE;
+        for (row, pivot) in self.rows.iter().zip(&self.pivots) {
+            direction[*pivot] = -row[free];
+        }
+        direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rationals(values: &[i128]) -> Vec<Rational> {
+        values.iter().map(|v| Rational::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4);
+        let third = Rational::new(-1, -3);
+
+        assert_eq!(half, Rational::new(1, 2));
+        assert_eq!(Rational::new(1, -2), -half);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert!(third < half);
+        assert_eq!((half + half).to_integer(), Some(1));
+        assert_eq!(half.to_integer(), None);
+        assert_eq!(format!("{} {}", half, Rational::from(3)), "1/2 3");
+    }
+
+    #[test]
+    fn test_unique() {
+        // The first claw machine from 2024 day 13
+        let system = LinearSystem::new(vec![vec![94, 22], vec![34, 67]], vec![8400, 5400]).unwrap();
+
+        assert_eq!(system.solve(), Solution::Unique(rationals(&[80, 40])));
+        assert_eq!(
+            system.non_negative_integer_solutions().unwrap(),
+            vec![vec![80, 40]]
+        );
+    }
+
+    #[test]
+    fn test_fractional() {
+        let system = LinearSystem::new(vec![vec![2, 0], vec![0, 4]], vec![1, 2]).unwrap();
+
+        let half = Rational::new(1, 2);
+        assert_eq!(system.solve(), Solution::Unique(vec![half, half]));
+        assert!(system.non_negative_integer_solutions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_none() {
+        let system = LinearSystem::new(vec![vec![1, 1], vec![2, 2]], vec![3, 7]).unwrap();
+
+        assert_eq!(system.solve(), Solution::None);
+        assert!(system.non_negative_integer_solutions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_infinite() {
+        // x + 2y = 4, repeated so it doesn't add anything
+        let system = LinearSystem::new(vec![vec![1, 2], vec![2, 4]], vec![4, 8]).unwrap();
+
+        assert_eq!(
+            system.solve(),
+            Solution::Infinite {
+                particular: rationals(&[4, 0]),
+                directions: vec![rationals(&[-2, 1])],
+            }
+        );
+        assert_eq!(
+            system.non_negative_integer_solutions().unwrap(),
+            vec![vec![4, 0], vec![2, 1], vec![0, 2]]
+        );
+    }
+
+    #[test]
+    fn test_cheapest() {
+        let system = LinearSystem::new(vec![vec![94, 22], vec![34, 67]], vec![8400, 5400]).unwrap();
+        assert_eq!(
+            system
+                .cheapest_non_negative_integer_solution(&[3, 1])
+                .unwrap(),
+            Some(vec![80, 40])
+        );
+
+        // Buttons moving the same way, where there are far too many ways to try them all
+        let target = 10_000_000_000_000;
+        let system =
+            LinearSystem::new(vec![vec![2, 4], vec![3, 6]], vec![2 * target, 3 * target]).unwrap();
+        assert!(system.non_negative_integer_solutions().is_err());
+        assert_eq!(
+            system
+                .cheapest_non_negative_integer_solution(&[3, 1])
+                .unwrap(),
+            Some(vec![0, target / 2])
+        );
+        assert_eq!(
+            system
+                .cheapest_non_negative_integer_solution(&[1, 3])
+                .unwrap(),
+            Some(vec![target, 0])
+        );
+
+        // Only every third step lands on whole numbers
+        let system = LinearSystem::new(vec![vec![3, 2]], vec![20]).unwrap();
+        let all = system.non_negative_integer_solutions().unwrap();
+        for costs in [[1, 1], [1, 2], [5, 1]] {
+            let cost = |s: &Vec<i128>| s[0] * costs[0] + s[1] * costs[1];
+            let expected = all.iter().min_by_key(|s| cost(s)).map(cost);
+            let cheapest = system
+                .cheapest_non_negative_integer_solution(&costs)
+                .unwrap();
+            assert_eq!(cheapest.as_ref().map(cost), expected);
+        }
+
+        // Odd and even never add up
+        let system = LinearSystem::new(vec![vec![2, 4]], vec![7]).unwrap();
+        assert_eq!(
+            system
+                .cheapest_non_negative_integer_solution(&[1, 1])
+                .unwrap(),
+            None
+        );
+
+        // x - y = 1 with y getting cheaper the more of it there is
+        let system = LinearSystem::new(vec![vec![1, -1]], vec![1]).unwrap();
+        assert_eq!(
+            system
+                .cheapest_non_negative_integer_solution(&[1, 1])
+                .unwrap(),
+            Some(vec![1, 0])
+        );
+        assert!(system
+            .cheapest_non_negative_integer_solution(&[1, -2])
+            .is_err());
+    }
+
+    #[test]
+    fn test_unbounded() {
+        // x - y = 1 has a solution for every y
+        let system = LinearSystem::new(vec![vec![1, -1]], vec![1]).unwrap();
+
+        assert!(matches!(system.solve(), Solution::Infinite { .. }));
+        assert!(system.non_negative_integer_solutions().is_err());
+    }
+
+    #[test]
+    fn test_new_checks_shape() {
+        assert!(LinearSystem::new(vec![vec![1, 2], vec![3]], vec![1, 2]).is_err());
+        assert!(LinearSystem::new(vec![vec![1, 2]], vec![1, 2]).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+use crate::prelude::*;
+use num_traits::{PrimInt, Signed};
+
+/// The greatest common divisor, which is never negative
+///
+/// `gcd(0, 0)` is zero. Panics if the result doesn't fit in a `T`, which can only happen for a
+/// signed `T` when both numbers are `T::min_value()` or zero, see `checked_gcd`.
+pub fn gcd<T: PrimInt>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("Greatest common divisor is too big for the type")
+}
+
+/// The greatest common divisor, or `None` if it doesn't fit in a `T`
+///
+/// Like `gcd(i64::MIN, 0)`, which is one more than `i64::MAX`.
+pub fn checked_gcd<T: PrimInt>(a: T, b: T) -> Option<T> {
+    if T::min_value() == T::zero() {
+        let (mut a, mut b) = (a, b);
+        while b != T::zero() {
+            (a, b) = (b, a % b);
+        }
+        return Some(a);
+    }
+
+    // Work with the negative of each number instead, as every positive value has one, but
+    // `T::min_value()` doesn't have a positive counterpart
+    let negative = |value: T| {
+        if value > T::zero() {
+            T::zero() - value
+        } else {
+            value
+        }
+    };
+    let minus_one = T::zero() - T::one();
+    let (mut a, mut b) = (negative(a), negative(b));
+    while b != T::zero() {
+        // Anything divides by -1 exactly, but `T::min_value() % -1` overflows
+        let remainder = if b == minus_one { T::zero() } else { a % b };
+        (a, b) = (b, remainder);
+    }
+    T::zero().checked_sub(&a)
+}
+
+/// The least common multiple, or `None` if it doesn't fit in a `T`
+///
+/// Anything and zero have a least common multiple of zero.
+pub fn lcm<T: PrimInt>(a: T, b: T) -> Option<T> {
+    if a == T::zero() || b == T::zero() {
+        return Some(T::zero());
+    }
+    (a / checked_gcd(a, b)?)
+        .checked_mul(&b)
+        .and_then(checked_abs)
+}
+
+/// The greatest common divisor of every number, zero if there are none
+///
+/// Returns `None` if the result doesn't fit in a `T`, which like `checked_gcd` only happens when
+/// every number is `T::min_value()` or zero.
+pub fn gcd_all<T: PrimInt>(numbers: &[T]) -> Option<T> {
+    // The only divisor too big for a `T` is the one `T::min_value()` is the negative of, so keep
+    // going with `T::min_value()` standing in for it, as that has the same divisors
+    let divisor = numbers.iter().fold(T::zero(), |acc, n| {
+        checked_gcd(acc, *n).unwrap_or_else(T::min_value)
+    });
+    checked_gcd(divisor, T::zero())
+}
+
+/// The least common multiple of every number, one if there are none
+///
+/// Handy for working out when several loops of different lengths all line up. Returns `None` if
+/// the result doesn't fit in a `T`.
+pub fn lcm_all<T: PrimInt>(numbers: &[T]) -> Option<T> {
+    numbers.iter().try_fold(T::one(), |acc, n| lcm(acc, *n))
+}
+
+/// The extended Euclidean algorithm
+///
+/// Returns `(g, x, y)` where `g` is the greatest common divisor and `a * x + b * y == g`.
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while r != T::zero() {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The number `x` in `0..modulus` where `a * x` is one more than a multiple of the modulus
+///
+/// Only exists when `a` and the modulus are coprime.
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(modulo(a, modulus), modulus);
+    (g == T::one()).then(|| modulo(x, modulus))
+}
+
+/// `a * b` reduced modulo the modulus, without overflowing even when `a * b` would
+pub fn mul_mod<T: PrimInt>(a: T, b: T, modulus: T) -> T {
+    let (a, mut b) = (modulo(a, modulus), modulo(b, modulus));
+    if let Some(product) = a.checked_mul(&b) {
+        return product % modulus;
+    }
+
+    // Fall back to double and add, keeping everything below the modulus
+    let mut result = T::zero();
+    let mut doubled = a;
+    while b > T::zero() {
+        if b & T::one() == T::one() {
+            result = add_mod(result, doubled, modulus);
+        }
+        doubled = add_mod(doubled, doubled, modulus);
+        b = b >> 1;
+    }
+    result
+}
+
+/// `base` to the power of `exponent`, reduced modulo the modulus
+///
+/// Works by repeated squaring, so huge exponents are fine. A negative exponent is treated as zero.
+pub fn mod_pow<T: PrimInt>(base: T, exponent: T, modulus: T) -> T {
+    let mut result = T::one() % modulus;
+    let mut base = modulo(base, modulus);
+    let mut exponent = exponent;
+
+    while exponent > T::zero() {
+        if exponent & T::one() == T::one() {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent = exponent >> 1;
+    }
+    result
+}
+
+/// Solve a system of congruences with the Chinese Remainder Theorem
+///
+/// Each congruence is a `(residue, modulus)` pair, meaning `x % modulus == residue`. The moduli
+/// don't have to be coprime, as long as the congruences agree with each other. Returns the smallest
+/// non-negative solution along with the least common multiple of the moduli, as every solution is
+/// a multiple of that away from the first.
+pub fn crt<T: PrimInt + Signed>(congruences: &[(T, T)]) -> Result<(T, T)> {
+    let mut solution = T::zero();
+    let mut combined_modulus = T::one();
+
+    for &(residue, modulus) in congruences {
+        if modulus <= T::zero() {
+            return Err(error!("CRT moduli must be positive"));
+        }
+        let residue = modulo(residue, modulus);
+
+        // We need solution + combined_modulus * k == residue (mod modulus), which only has an
+        // answer if the difference is a multiple of the gcd
+        let g = gcd(combined_modulus, modulus);
+        let difference = residue - modulo(solution, modulus);
+        if difference % g != T::zero() {
+            return Err(error!("CRT congruences have no common solution"));
+        }
+
+        let reduced_modulus = modulus / g;
+        let inverse = mod_inverse(combined_modulus / g, reduced_modulus)
+            .ok_or(error!("CRT moduli are not invertible"))?;
+        let k = mul_mod(difference / g, inverse, reduced_modulus);
+
+        let next_modulus = combined_modulus
+            .checked_mul(&reduced_modulus)
+            .ok_or(error!("CRT modulus overflows"))?;
+        solution = add_mod(
+            solution,
+            mul_mod(combined_modulus, k, next_modulus),
+            next_modulus,
+        );
+        combined_modulus = next_modulus;
+    }
+
+    Ok((solution, combined_modulus))
+}
+
+fn checked_abs<T: PrimInt>(value: T) -> Option<T> {
+    if value < T::zero() {
+        T::zero().checked_sub(&value)
+    } else {
+        Some(value)
+    }
+}
+
+/// The remainder, always in `0..modulus` even for negative values
+fn modulo<T: PrimInt>(value: T, modulus: T) -> T {
+    let remainder = value % modulus;
+    if remainder < T::zero() {
+        remainder + modulus
+    } else {
+        remainder
+    }
+}
+
+/// `a + b` reduced modulo the modulus, for `a` and `b` already in `0..modulus`
+fn add_mod<T: PrimInt>(a: T, b: T, modulus: T) -> T {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i32, 18), 6);
+        assert_eq!(gcd(0u8, 0), 0);
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(-4i64, 6), Some(12));
+        assert_eq!(lcm(0u64, 6), Some(0));
+        assert_eq!(lcm(200u8, 3), None);
+    }
+
+    #[test]
+    fn test_smallest_signed_value() {
+        assert_eq!(checked_gcd(i64::MIN, 0), None);
+        assert_eq!(checked_gcd(i64::MIN, i64::MIN), None);
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(gcd(i8::MIN, -1), 1);
+        assert_eq!(gcd(i8::MIN, 96), 32);
+        assert_eq!(lcm(i8::MIN, 1), None);
+        assert_eq!(lcm(i8::MIN, 2), None);
+        assert_eq!(lcm(-64i8, 2), Some(64));
+        assert_eq!(gcd_all(&[i32::MIN, 0]), None);
+        assert_eq!(gcd_all(&[0, i32::MIN, i32::MIN]), None);
+        assert_eq!(gcd_all(&[i32::MIN, 0, 6]), Some(2));
+        assert_eq!(gcd_all(&[i32::MIN, i32::MIN, 6]), Some(2));
+        assert_eq!(gcd_all(&[i32::MIN]), None);
+    }
+
+    #[test]
+    fn test_over_slices() {
+        assert_eq!(gcd_all(&[12u32, 18, 27]), Some(3));
+        assert_eq!(gcd_all(&[-12i32]), Some(12));
+        assert_eq!(gcd_all::<u32>(&[]), Some(0));
+        assert_eq!(lcm_all(&[2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all::<u64>(&[]), Some(1));
+        assert_eq!(lcm_all(&[16u8, 15]), Some(240));
+        assert_eq!(lcm_all(&[16u8, 15, 2, 7]), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240i64, 46), (46, 240), (-240, 46), (17, 5), (0, 7), (7, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3i32, 11), Some(4));
+        assert_eq!(mod_inverse(-3i32, 11), Some(7));
+        assert_eq!(mod_inverse(6i32, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2u32, 10, 1000), 24);
+        assert_eq!(mod_pow(3u64, 0, 7), 1);
+        assert_eq!(mod_pow(5u64, 3, 1), 0);
+        assert_eq!(mod_pow(-2i64, 3, 5), 2);
+
+        // Big enough that squaring the base would overflow without mul_mod
+        let modulus = u64::MAX - 58;
+        assert_eq!(mod_pow(2, modulus - 1, modulus), 1);
+    }
+
+    #[test]
+    fn test_crt_coprime() {
+        // The classic x = 2 (mod 3), x = 3 (mod 5), x = 2 (mod 7)
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]).unwrap(), (23, 105));
+        assert_eq!(crt::<i64>(&[]).unwrap(), (0, 1));
+        assert_eq!(crt(&[(-1i64, 4)]).unwrap(), (3, 4));
+    }
+
+    #[test]
+    fn test_crt_not_coprime() {
+        assert_eq!(crt(&[(2i64, 6), (8, 10)]).unwrap(), (8, 30));
+        assert!(crt(&[(1i64, 6), (2, 10)]).is_err());
+        assert!(crt(&[(0i64, 0)]).is_err());
+    }
+}
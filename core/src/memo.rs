@@ -0,0 +1,283 @@
+use crate::prelude::*;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Somewhere to keep the results a `Memo` has already worked out
+pub trait MemoStore<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq, V> MemoStore<K, V> for HashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+}
+
+/// A store for small `usize` keys, kept in a `Vec` instead of being hashed
+///
+/// Grows to fit the largest key it's given, so it's best when the keys are packed close to zero,
+/// like an index into the input.
+#[derive(Debug, Clone)]
+pub struct DenseStore<V> {
+    values: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V> DenseStore<V> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Make room for keys up to `capacity` up front
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut values = Vec::with_capacity(capacity);
+        values.resize_with(capacity, || None);
+        Self { values, len: 0 }
+    }
+}
+
+impl<V> Default for DenseStore<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> MemoStore<usize, V> for DenseStore<V> {
+    fn get(&self, key: &usize) -> Option<&V> {
+        self.values.get(*key)?.as_ref()
+    }
+
+    fn insert(&mut self, key: usize, value: V) {
+        if key >= self.values.len() {
+            self.values.resize_with(key + 1, || None);
+        }
+        if self.values[key].replace(value).is_none() {
+            self.len += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.values.iter_mut().for_each(|value| *value = None);
+        self.len = 0;
+    }
+}
+
+/// How often a `Memo` found what it was looking for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl MemoStats {
+    /// The fraction of lookups that were already cached, zero if nothing was looked up
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// A cache for a recursive function, keyed by its arguments
+///
+/// The function does its recursion through the `Memo` it's handed, so a count that would
+/// otherwise thread a `&mut HashMap` everywhere looks like this:
+///
+/// ```
+/// use advent::memo::Memo;
+///
+/// fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     if n < 2 {
+///         return n;
+///     }
+///     memo.get_or_compute(n, |memo| fibonacci(memo, n - 1) + fibonacci(memo, n - 2))
+/// }
+///
+/// assert_eq!(fibonacci(&mut Memo::new(), 90), 2_880_067_194_370_816_120);
+/// ```
+///
+/// It's backed by a hashbrown `HashMap` unless a different `MemoStore` is picked with
+/// `with_store`, such as a `DenseStore` when the keys are small numbers.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, S = HashMap<K, V>> {
+    store: S,
+    stats: MemoStats,
+    _marker: PhantomData<(K, V)>,
+}
+
+/// A `Memo` for small `usize` keys
+pub type DenseMemo<V> = Memo<usize, V, DenseStore<V>>;
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_store(HashMap::new())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> DenseMemo<V> {
+    pub fn dense(capacity: usize) -> Self {
+        Self::with_store(DenseStore::with_capacity(capacity))
+    }
+}
+
+impl<K, V, S> Memo<K, V, S> {
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            stats: MemoStats::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V: Clone, S: MemoStore<K, V>> Memo<K, V, S> {
+    /// The cached value for the key, working it out with `compute` if it isn't there yet
+    ///
+    /// `compute` is given the memo back, so it can recurse through it.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+
+        let value = compute(self);
+        self.store.insert(key, value.clone());
+        value
+    }
+
+    /// Like `get_or_compute` for functions that can fail, errors are not cached
+    pub fn try_get_or_compute(
+        &mut self,
+        key: K,
+        compute: impl FnOnce(&mut Self) -> Result<V>,
+    ) -> Result<V> {
+        if let Some(value) = self.lookup(&key) {
+            return Ok(value);
+        }
+
+        let value = compute(self)?;
+        self.store.insert(key, value.clone());
+        Ok(value)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// The number of cached values
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Forget every cached value, along with the stats
+    pub fn clear(&mut self) {
+        self.store.clear();
+        self.stats = MemoStats::default();
+    }
+
+    fn lookup(&mut self, key: &K) -> Option<V> {
+        let value = self.store.get(key).cloned();
+        if value.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci<S: MemoStore<usize, u64>>(memo: &mut Memo<usize, u64, S>, n: usize) -> u64 {
+        if n < 2 {
+            return n as u64;
+        }
+        memo.get_or_compute(n, |memo| fibonacci(memo, n - 1) + fibonacci(memo, n - 2))
+    }
+
+    #[test]
+    fn test_hash_memo() {
+        let mut memo = Memo::new();
+
+        assert_eq!(fibonacci(&mut memo, 50), 12_586_269_025);
+        // Every n from 2 to 50 is worked out once, and all but the top two are looked up again when
+        // they come round as the n - 2 of a bigger n
+        assert_eq!(memo.len(), 49);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 47,
+                misses: 49
+            }
+        );
+
+        assert_eq!(fibonacci(&mut memo, 50), 12_586_269_025);
+        assert_eq!(memo.stats().hits, 48);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), MemoStats::default());
+    }
+
+    #[test]
+    fn test_dense_memo() {
+        let mut memo = DenseMemo::dense(10);
+
+        assert_eq!(fibonacci(&mut memo, 50), 12_586_269_025);
+        assert_eq!(memo.len(), 49);
+        assert!((memo.stats().hit_rate() - 47.0 / 96.0).abs() < 1e-9);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(fibonacci(&mut memo, 10), 55);
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let mut memo: Memo<u32, u32> = Memo::new();
+
+        assert!(memo
+            .try_get_or_compute(1, |_| Err(AdventError::InvalidInput))
+            .is_err());
+        assert!(memo.is_empty());
+        assert_eq!(memo.try_get_or_compute(1, |_| Ok(5)).unwrap(), 5);
+        assert_eq!(memo.try_get_or_compute(1, |_| Ok(6)).unwrap(), 5);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::coordinates::{Grid, GridStorage};
+use crate::prelude::*;
+
+/// One of the block letter fonts that puzzles draw their answers in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Font {
+    /// Letters 4 wide and 6 tall, one blank column apart, as on the 2022 day 10 CRT
+    ///
+    /// `I` is only 3 wide and `Y` is 5, filling the gap after it.
+    Small,
+    /// Letters 6 wide and 10 tall, two blank columns apart, as in the 2018 day 10 sky
+    Large,
+}
+
+impl Font {
+    /// The font with letters of the given height, if there is one
+    pub fn for_height(height: usize) -> Option<Self> {
+        match height {
+            6 => Some(Font::Small),
+            10 => Some(Font::Large),
+            _ => None,
+        }
+    }
+
+    /// How wide most of the letters are
+    pub fn glyph_width(&self) -> usize {
+        match self {
+            Font::Small => 4,
+            Font::Large => 6,
+        }
+    }
+
+    pub fn glyph_height(&self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    /// The distance from the start of one letter to the start of the next, for the usual width
+    pub fn stride(&self) -> usize {
+        match self {
+            Font::Small => 5,
+            Font::Large => 8,
+        }
+    }
+
+    /// Every letter along with the rows of its pattern, widest first so that a narrow letter is
+    /// never picked when a wider one covers more of the image
+    fn glyphs(&self) -> Vec<(char, Vec<&'static str>)> {
+        let glyphs = match self {
+            Font::Small => SMALL_GLYPHS,
+            Font::Large => LARGE_GLYPHS,
+        };
+        let mut glyphs: Vec<_> = glyphs
+            .iter()
+            .map(|(letter, pattern)| (*letter, pattern.lines().collect::<Vec<_>>()))
+            .collect();
+        glyphs.sort_by_key(|(_, rows)| std::cmp::Reverse(rows[0].len()));
+        glyphs
+    }
+}
+
+/// Read the letters out of an image drawn with `#` for lit pixels and `.` or spaces for dark ones
+///
+/// Blank rows and columns around the edge are ignored, and the font is picked from how tall the
+/// letters are.
+pub fn recognise(image: &str) -> Result<String> {
+    let rows: Vec<Vec<bool>> = image
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+    // Lines that had their trailing dark pixels trimmed off are padded back out
+    let rows = rows
+        .into_iter()
+        .map(|mut row| {
+            row.resize(width, false);
+            row
+        })
+        .collect();
+
+    recognise_grid(&Grid::from_rows(rows)?, |lit| *lit)
+}
+
+/// Read the letters out of a grid, where `lit` says which cells are part of a letter
+pub fn recognise_grid<T, F>(grid: &Grid<T>, lit: F) -> Result<String>
+where
+    F: Fn(&T) -> bool,
+{
+    let (height, width) = grid.dimensions();
+    let lit_row = |row: usize| (0..width).any(|col| lit(grid.cell(row, col)));
+    let lit_col = |col: usize| (0..height).any(|row| lit(grid.cell(row, col)));
+
+    let (Some(top), Some(left)) = (
+        (0..height).find(|&r| lit_row(r)),
+        (0..width).find(|&c| lit_col(c)),
+    ) else {
+        return Err(error!("There are no letters in the image"));
+    };
+    let bottom = (0..height).rev().find(|&r| lit_row(r)).unwrap_or(top);
+    let right = (0..width).rev().find(|&c| lit_col(c)).unwrap_or(left);
+
+    let font = Font::for_height(bottom - top + 1).ok_or(error!(
+        "Letters {} pixels tall don't match any known font",
+        bottom - top + 1
+    ))?;
+
+    let pixel = |row: usize, col: usize| col < width && lit(grid.cell(row, col));
+    let blank = |col: usize| !(top..=bottom).any(|row| pixel(row, col));
+    let glyphs = font.glyphs();
+
+    // Letters aren't all the same width, so rather than stepping along by a fixed amount, read a
+    // letter and then skip the blank columns after it. A letter narrower than the spacing has to
+    // be followed by a blank column, so a narrow letter can't match the start of a wider one.
+    let mut letters = String::new();
+    let mut unknown = Vec::new();
+    let mut start = left;
+    while start <= right {
+        let found = glyphs.iter().find(|(_, rows)| {
+            let glyph_width = rows[0].len();
+            rows.iter().enumerate().all(|(r, pattern)| {
+                pattern
+                    .chars()
+                    .enumerate()
+                    .all(|(c, p)| (p == '#') == pixel(top + r, start + c))
+            }) && (glyph_width >= font.stride() || blank(start + glyph_width))
+        });
+
+        match found {
+            Some((letter, rows)) => {
+                letters.push(*letter);
+                start += rows[0].len();
+            }
+            None => {
+                let glyph = (top..=bottom)
+                    .map(|row| {
+                        (start..start + font.glyph_width())
+                            .map(|col| if pixel(row, col) { '#' } else { '.' })
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                unknown.push((letters.chars().count(), glyph));
+                letters.push('?');
+                start += font.glyph_width();
+            }
+        }
+
+        while start <= right && blank(start) {
+            start += 1;
+        }
+    }
+
+    if !unknown.is_empty() {
+        let listing = unknown
+            .iter()
+            .map(|(idx, glyph)| format!("letter {}:\n{}", idx + 1, glyph))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        return Err(error!(
+            "Unable to read {} of the letters in {:?}\n\n{}",
+            unknown.len(),
+            letters,
+            listing
+        ));
+    }
+
+    Ok(letters)
+}
+
+const SMALL_GLYPHS: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_GLYPHS: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: &str = "\
+#..#.####.#....#.....##..
+#..#.#....#....#....#..#.
+####.###..#....#....#..#.
+#..#.#....#....#....#..#.
+#..#.#....#....#....#..#.
+#..#.####.####.####..##..";
+
+    #[test]
+    fn test_small_font() {
+        assert_eq!(recognise(HELLO).unwrap(), "HELLO");
+
+        // Surrounding blank space and trimmed lines are fine
+        let padded = format!("\n{}\n", HELLO.replace('.', " ").replace(" \n", "\n"));
+        assert_eq!(recognise(&padded).unwrap(), "HELLO");
+    }
+
+    #[test]
+    fn test_narrow_and_wide_letters() {
+        // I is narrower than the rest, and Y runs right up to the letter after it
+        let image = [
+            "###.#...##...",
+            ".#..#...##...",
+            ".#...#.#.#...",
+            ".#....#..#...",
+            ".#....#..#...",
+            "###...#..####",
+        ]
+        .join("\n");
+
+        assert_eq!(recognise(&image).unwrap(), "IYL");
+    }
+
+    #[test]
+    fn test_large_font() {
+        let image = [
+            "#....#..######",
+            "#....#..#.....",
+            ".#..#...#.....",
+            ".#..#...#.....",
+            "..##....#####.",
+            "..##....#.....",
+            ".#..#...#.....",
+            ".#..#...#.....",
+            "#....#..#.....",
+            "#....#..######",
+        ]
+        .join("\n");
+
+        assert_eq!(recognise(&image).unwrap(), "XE");
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::parse(HELLO, |c| Some(c == '#')).unwrap();
+
+        assert_eq!(recognise_grid(&grid, |lit| *lit).unwrap(), "HELLO");
+    }
+
+    #[test]
+    fn test_unknown_glyphs() {
+        let image = HELLO.replacen("#..#.####", "##.#.####", 1);
+        let err = recognise(&image).unwrap_err().to_string();
+
+        assert!(err.contains("\"?ELLO\""), "{}", err);
+        assert!(err.contains("letter 1:\n##.#\n#..#"), "{}", err);
+
+        assert!(recognise("....\n....").is_err());
+        assert!(recognise("#\n#\n#").is_err());
+    }
+}
@@ -1,12 +1,24 @@
+use crate::error_handling::AdventError;
 use nom::{
     character::complete::{char, digit1},
     combinator::{map_res, opt, recognize},
+    error::{Error, ErrorKind, ParseError},
     sequence::preceded,
     IResult, Parser,
 };
 use num_traits::{Signed, Unsigned};
 use std::str::FromStr;
 
+mod columns;
+mod grid;
+mod numbers;
+mod verbose;
+
+pub use columns::TextBlock;
+pub use grid::{nom_grid, nom_grid_with_markers, Markers};
+pub use numbers::{extract_array, extract_numbers};
+pub use verbose::{VerboseError, VerboseErrorKind};
+
 pub fn nom_unsigned_digit<T>(input: &str) -> IResult<&str, T>
 where
     T: FromStr + Unsigned,
@@ -24,6 +36,46 @@ where
     .parse(input)
 }
 
+/// Run `parser` over all of `input`, failing if anything but whitespace is left over
+///
+/// This is the way in for a day's `parse_input`, so a mistake halfway through the input is an error
+/// pointing at where parsing stopped, rather than the rest quietly going missing.
+pub fn parse_all<'a, P>(parser: P, input: &'a str) -> Result<P::Output, AdventError>
+where
+    P: Parser<&'a str, Error = Error<&'a str>>,
+{
+    parse_all_with(parser, input, AdventError::from)
+}
+
+/// Like `parse_all`, for a parser using `VerboseError` so its `context` labels end up in the error
+pub fn parse_all_verbose<'a, P>(parser: P, input: &'a str) -> Result<P::Output, AdventError>
+where
+    P: Parser<&'a str, Error = VerboseError<&'a str>>,
+{
+    parse_all_with(parser, input, AdventError::from_verbose)
+}
+
+fn parse_all_with<'a, P, E>(
+    mut parser: P,
+    input: &'a str,
+    convert: fn(nom::Err<E>) -> AdventError,
+) -> Result<P::Output, AdventError>
+where
+    P: Parser<&'a str, Error = E>,
+    E: ParseError<&'a str>,
+{
+    let (rest, output) = parser
+        .parse(input)
+        .map_err(|e| convert(e).with_whole_input(input))?;
+    // Point at the leftover text itself rather than the newline before it
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        let leftover = nom::Err::Error(E::from_error_kind(rest, ErrorKind::Eof));
+        return Err(convert(leftover).with_whole_input(input));
+    }
+    Ok(output)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,4 +93,53 @@ mod tests {
         assert_eq!(nom_signed_digit::<i64>("123"), Ok(("", 123i64)));
         assert_eq!(nom_signed_digit::<i64>("-123"), Ok(("", -123i64)));
     }
+
+    #[test]
+    fn test_parse_all() {
+        use nom::{character::complete::newline, multi::separated_list1};
+
+        let numbers = || separated_list1(newline, nom_unsigned_digit::<u32>);
+        assert_eq!(parse_all(numbers(), "1\n2\n3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(
+            parse_all(numbers(), "1\n2\n3\n\n  \n").unwrap(),
+            vec![1, 2, 3]
+        );
+
+        let err = parse_all(numbers(), "1\n2\nx\n4\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: line 3, column 1: expected the end of the input\n3 | x\n  | ^"
+        );
+
+        let err = parse_all(numbers(), "x").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Parsing error: line 1, column 1"));
+    }
+
+    #[test]
+    fn test_parse_all_verbose() {
+        use nom::{character::complete::newline, error::context, multi::separated_list1};
+
+        let numbers = || {
+            separated_list1(
+                newline,
+                context("number", map_res(digit1, |s: &str| s.parse::<u32>())),
+            )
+        };
+        assert_eq!(parse_all_verbose(numbers(), "1\n2\n").unwrap(), vec![1, 2]);
+
+        let err = parse_all_verbose(numbers(), "1\n2\nx\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: line 3, column 1: expected the end of the input\n3 | x\n  | ^"
+        );
+
+        let err = parse_all_verbose(context("number", digit1), "x").unwrap_err();
+        assert!(
+            err.to_string().contains("expected a digit in number"),
+            "{}",
+            err
+        );
+    }
 }
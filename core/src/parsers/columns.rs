@@ -0,0 +1,180 @@
+use crate::prelude::*;
+use std::slice::SliceIndex;
+
+/// A block of text to be read in columns, like a diagram of stacked crates or sums written
+/// top to bottom
+///
+/// Short lines are padded with spaces so every line is the same width, which keeps each column
+/// lined up even when an editor has stripped the trailing spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextBlock {
+    lines: Vec<Vec<char>>,
+    width: usize,
+}
+
+impl TextBlock {
+    pub fn new(input: &str) -> Self {
+        let lines: Vec<&str> = input.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines = lines
+            .into_iter()
+            .map(|line| {
+                let mut chars: Vec<char> = line.chars().collect();
+                chars.resize(width, ' ');
+                chars
+            })
+            .collect();
+        Self { lines, width }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Each line, padded out to the full width
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.lines.iter().map(|line| line.iter().collect())
+    }
+
+    /// A column read from top to bottom
+    pub fn column(&self, column: usize) -> Option<String> {
+        (column < self.width).then(|| self.lines.iter().map(|line| line[column]).collect())
+    }
+
+    /// Every column read from top to bottom, which is the block turned on its side
+    pub fn columns(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.width).filter_map(|column| self.column(column))
+    }
+
+    /// Whether a column is only spaces, which a column past the right hand edge isn't
+    pub fn is_blank_column(&self, column: usize) -> bool {
+        column < self.width && self.lines.iter().all(|line| line[column] == ' ')
+    }
+
+    /// Only some of the lines, keeping the full width
+    pub fn rows<R>(&self, rows: R) -> Self
+    where
+        R: SliceIndex<[Vec<char>], Output = [Vec<char>]>,
+    {
+        let lines = self.lines.get(rows).unwrap_or_default();
+        Self {
+            lines: lines.to_vec(),
+            width: self.width,
+        }
+    }
+
+    /// Only the columns in `start..end`, like a fixed-width field
+    pub fn slice_columns(&self, start: usize, end: usize) -> Self {
+        let end = end.min(self.width);
+        let start = start.min(end);
+        Self {
+            lines: self
+                .lines
+                .iter()
+                .map(|line| line[start..end].to_vec())
+                .collect(),
+            width: end - start,
+        }
+    }
+
+    /// Split the block wherever there's a column that's blank all the way down
+    ///
+    /// Runs of blank columns count as one separator, and blank columns at the edges are dropped.
+    pub fn split_on_blank_columns(&self) -> Vec<Self> {
+        let mut sections = Vec::new();
+        let mut start = None;
+        for column in 0..=self.width {
+            let blank = column == self.width || self.is_blank_column(column);
+            match (blank, start) {
+                (true, Some(from)) => {
+                    sections.push(self.slice_columns(from, column));
+                    start = None;
+                }
+                (false, None) => start = Some(column),
+                _ => {}
+            }
+        }
+        sections
+    }
+
+    /// Read each column as a number with its digits going from top to bottom
+    ///
+    /// Anything that isn't a digit is skipped over, and columns without any digits don't give a
+    /// number at all.
+    pub fn vertical_numbers<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        AdventError: From<T::Err>,
+    {
+        self.columns()
+            .map(|column| column.replace(|c: char| !c.is_ascii_digit(), ""))
+            .filter(|digits| !digits.is_empty())
+            .map(|digits| Ok(digits.parse()?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRATES: &str = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+
+    #[test]
+    fn test_padding() {
+        let block = TextBlock::new(CRATES);
+
+        assert_eq!(block.width(), 11);
+        assert_eq!(block.height(), 4);
+        assert_eq!(block.lines().next().unwrap(), "    [D]    ");
+        assert_eq!(block.column(5).unwrap(), "DCM2");
+        assert_eq!(block.column(9).unwrap(), "  P3");
+        assert_eq!(block.column(11), None);
+        assert_eq!(block.columns().count(), 11);
+        assert!(block.is_blank_column(3));
+        assert!(!block.is_blank_column(5));
+        assert!(!block.is_blank_column(11));
+    }
+
+    #[test]
+    fn test_split_on_blank_columns() {
+        let sections = TextBlock::new(CRATES).split_on_blank_columns();
+
+        assert_eq!(sections.len(), 3);
+        assert_eq!(
+            sections
+                .iter()
+                .map(|s| s.column(1).unwrap())
+                .collect::<Vec<_>>(),
+            vec![" NZ1", "DCM2", "  P3"]
+        );
+        assert_eq!(
+            sections[2].lines().collect::<Vec<_>>(),
+            vec!["   ", "   ", "[P]", " 3 "]
+        );
+
+        assert!(TextBlock::new("   \n  ")
+            .split_on_blank_columns()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_vertical_numbers() {
+        let block = TextBlock::new("123 328\n 45 64 \n  6 98 \n*   +  ");
+        let sections = block.split_on_blank_columns();
+        let numbers = sections[0].rows(..3).vertical_numbers::<u64>().unwrap();
+        assert_eq!(numbers, vec![1, 24, 356]);
+
+        let numbers = sections[1].rows(..3).vertical_numbers::<u64>().unwrap();
+        assert_eq!(numbers, vec![369, 248, 8]);
+        assert_eq!(sections[1].rows(3..).lines().next().unwrap(), "+  ");
+    }
+}
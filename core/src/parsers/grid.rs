@@ -0,0 +1,178 @@
+use crate::prelude::*;
+use nom::error::{Error, ErrorKind};
+
+/// Where the marker characters turned up while parsing a grid, like the `S` and `E` of a maze
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markers {
+    positions: HashMap<char, Vec<GridCoordinate<usize>>>,
+}
+
+impl Markers {
+    /// The first place the marker was found, reading row by row
+    pub fn get(&self, marker: char) -> Option<GridCoordinate<usize>> {
+        self.all(marker).first().copied()
+    }
+
+    /// Every place the marker was found, row by row
+    pub fn all(&self, marker: char) -> &[GridCoordinate<usize>] {
+        self.positions.get(&marker).map_or(&[], Vec::as_slice)
+    }
+
+    /// Like `get`, but a missing marker is an error
+    pub fn require(&self, marker: char) -> Result<GridCoordinate<usize>> {
+        self.get(marker)
+            .ok_or(error!("No {:?} marker in the grid", marker))
+    }
+}
+
+/// Parse lines of characters into a grid, turning each one into a cell with `TryFrom<char>`
+///
+/// The grid runs until a blank line or the end of the input, and the newline after the last row
+/// is left alone, so it can sit in the middle of a bigger parser.
+pub fn nom_grid<T: TryFrom<char>>(input: &str) -> IResult<&str, Grid<T>> {
+    let (rest, (grid, _)) = parse_grid(input, &[])?;
+    Ok((rest, grid))
+}
+
+/// Like `nom_grid`, but also note down where each of the `markers` characters is
+///
+/// The markers are still turned into cells like any other character.
+pub fn nom_grid_with_markers<T: TryFrom<char>>(
+    markers: &[char],
+) -> impl FnMut(&str) -> IResult<&str, (Grid<T>, Markers)> + '_ {
+    move |input| parse_grid(input, markers)
+}
+
+/// Every row has to be as wide as the first. Failures point at the character that couldn't be
+/// turned into a cell, or the start of a row that's the wrong width.
+fn parse_grid<'a, T: TryFrom<char>>(
+    input: &'a str,
+    markers: &[char],
+) -> IResult<&'a str, (Grid<T>, Markers)> {
+    let mut cells = Vec::new();
+    let mut found = Markers::default();
+    let mut width = None;
+    let mut row = 0;
+    let mut rest = input;
+    let mut end = input;
+
+    loop {
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        let line = rest[..line_end].trim_end_matches('\r');
+        if line.is_empty() {
+            break;
+        }
+
+        let mut line_width = 0;
+        for (offset, c) in line.char_indices() {
+            let cell = T::try_from(c)
+                .map_err(|_| nom::Err::Failure(Error::new(&rest[offset..], ErrorKind::Char)))?;
+            if markers.contains(&c) {
+                found
+                    .positions
+                    .entry(c)
+                    .or_default()
+                    .push(GridCoordinate::new(row, line_width));
+            }
+            cells.push(cell);
+            line_width += 1;
+        }
+
+        if *width.get_or_insert(line_width) != line_width {
+            return Err(nom::Err::Failure(Error::new(rest, ErrorKind::Verify)));
+        }
+        row += 1;
+
+        // The newline after a row is only ours if there's another row after it
+        end = &rest[line.len()..];
+        match rest[line_end..].strip_prefix('\n') {
+            Some(next) => rest = next,
+            None => break,
+        }
+    }
+
+    let Some(width) = width else {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::Many1)));
+    };
+    let grid = Grid::from_vec(row, width, cells)
+        .map_err(|_| nom::Err::Failure(Error::new(input, ErrorKind::Verify)))?;
+
+    Ok((end, (grid, found)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tile {
+        Wall,
+        Floor,
+        Robot,
+    }
+
+    impl TryFrom<char> for Tile {
+        type Error = AdventError;
+
+        fn try_from(c: char) -> Result<Self> {
+            match c {
+                '#' => Ok(Tile::Wall),
+                '.' => Ok(Tile::Floor),
+                '@' => Ok(Tile::Robot),
+                _ => Err(AdventError::InvalidInput),
+            }
+        }
+    }
+
+    #[test]
+    fn test_nom_grid() {
+        let (rest, grid) = nom_grid::<Tile>("#.#\n.@.\n\n<>^").unwrap();
+
+        assert_eq!(rest, "\n\n<>^");
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid[GridCoordinate::new(1usize, 1usize)], Tile::Robot);
+
+        let (rest, grid) = nom_grid::<Tile>("#.\r\n.#\r\n").unwrap();
+        assert_eq!(rest, "\r\n");
+        assert_eq!(grid.row(1), Some(&[Tile::Floor, Tile::Wall][..]));
+    }
+
+    #[test]
+    fn test_markers() {
+        let (_, (grid, markers)) = nom_grid_with_markers::<Tile>(&['@', '#'])
+            .parse("#.@\n@.#")
+            .unwrap();
+
+        assert_eq!(grid.find(&Tile::Robot), Some(GridCoordinate::new(0, 2)));
+        assert_eq!(markers.get('@'), Some(GridCoordinate::new(0, 2)));
+        assert_eq!(
+            markers.all('#'),
+            &[GridCoordinate::new(0, 0), GridCoordinate::new(1, 2)]
+        );
+        assert_eq!(markers.get('.'), None);
+        assert!(markers.require('.').is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = "#.#\n.x.";
+        match nom_grid::<Tile>(input) {
+            Err(nom::Err::Failure(e)) => {
+                assert_eq!(e.input, "x.");
+                assert_eq!(e.code, ErrorKind::Char);
+            }
+            other => panic!("Expected a failure, got {:?}", other),
+        }
+
+        match nom_grid::<Tile>("#.#\n.#\n###") {
+            Err(nom::Err::Failure(e)) => {
+                assert_eq!(e.input, ".#\n###");
+                assert_eq!(e.code, ErrorKind::Verify);
+            }
+            other => panic!("Expected a failure, got {:?}", other),
+        }
+
+        assert!(nom_grid::<Tile>("\n#").is_err());
+    }
+}
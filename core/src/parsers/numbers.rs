@@ -0,0 +1,113 @@
+use crate::prelude::*;
+
+/// Pull every integer out of a line, ignoring whatever text is around them
+///
+/// A `-` or `+` right before the digits is read as a sign, unless it follows a digit, so
+/// `x=-2, y=+3` gives `[-2, 3]` while a range like `2-4` gives `[2, 4]`. Works on `&str` and
+/// `&[u8]` without copying the input.
+pub fn extract_numbers<T>(line: impl AsRef<[u8]>) -> Result<Vec<T>>
+where
+    T: FromStr,
+    AdventError: From<T::Err>,
+{
+    let numbers = number_slices(line.as_ref())
+        .map(parse_number)
+        .collect::<Result<Vec<T>>>()?;
+    if numbers.is_empty() {
+        return Err(AdventError::NoNumbers);
+    }
+    Ok(numbers)
+}
+
+/// Like `extract_numbers`, but the line has to hold exactly `N` of them
+pub fn extract_array<T, const N: usize>(line: impl AsRef<[u8]>) -> Result<[T; N]>
+where
+    T: FromStr,
+    AdventError: From<T::Err>,
+{
+    let line = line.as_ref();
+    let numbers = extract_numbers::<T>(line)?;
+    let found = numbers.len();
+    numbers.try_into().map_err(|_| {
+        error!(
+            "Expected {} numbers but found {} in {:?}",
+            N,
+            found,
+            String::from_utf8_lossy(line)
+        )
+    })
+}
+
+/// The slices of `line` that make up each number, sign included
+fn number_slices(line: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        let start = position + line[position..].iter().position(u8::is_ascii_digit)?;
+        let mut end = start;
+        while end < line.len() && line[end].is_ascii_digit() {
+            end += 1;
+        }
+        position = end;
+
+        let signed = start > 0
+            && matches!(line[start - 1], b'-' | b'+')
+            && (start < 2 || !line[start - 2].is_ascii_digit());
+        Some(&line[start - signed as usize..end])
+    })
+}
+
+fn parse_number<T>(digits: &[u8]) -> Result<T>
+where
+    T: FromStr,
+    AdventError: From<T::Err>,
+{
+    // Only ever ASCII digits and a sign, so this can't fail
+    let digits = std::str::from_utf8(digits).map_err(|_| AdventError::InvalidInput)?;
+    Ok(digits.parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_numbers() {
+        assert_eq!(
+            extract_numbers::<u64>("Button A: X+94, Y+34").unwrap(),
+            vec![94, 34]
+        );
+        assert_eq!(
+            extract_numbers::<i32>("Sensor at x=2, y=-18: closest beacon is at x=-2, y=15")
+                .unwrap(),
+            vec![2, -18, -2, 15]
+        );
+        assert_eq!(
+            extract_numbers::<i32>(b"2-4,6-8".as_slice()).unwrap(),
+            vec![2, 4, 6, 8]
+        );
+        assert_eq!(extract_numbers::<i8>("--5 a-1").unwrap(), vec![-5, -1]);
+
+        assert!(matches!(
+            extract_numbers::<u32>("no numbers here"),
+            Err(AdventError::NoNumbers)
+        ));
+        assert!(extract_numbers::<u32>("x=-2").is_err());
+        assert!(extract_numbers::<u8>("256").is_err());
+    }
+
+    #[test]
+    fn test_extract_array() {
+        let [x, y] = extract_array::<i64, 2>("Prize: X=8400, Y=5400").unwrap();
+        assert_eq!((x, y), (8400, 5400));
+
+        let err = extract_array::<u32, 3>("1, 2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error: Expected 3 numbers but found 2 in \"1, 2\""
+        );
+        assert!(matches!(
+            extract_array::<u32, 1>(b"".as_slice()),
+            Err(AdventError::NoNumbers)
+        ));
+    }
+}
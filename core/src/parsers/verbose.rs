@@ -0,0 +1,53 @@
+use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError};
+
+/// A nom error that remembers every step on the way out of a failed parse
+///
+/// This is what `nom::error::context` labels get attached to, so a parser written as
+/// `IResult<&str, T, VerboseError<&str>>` can say what it was in the middle of when it failed.
+/// Frames are stored innermost first, which is the order nom adds them in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerboseError<I> {
+    pub errors: Vec<(I, VerboseErrorKind)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerboseErrorKind {
+    /// A label added with `context`
+    Context(&'static str),
+    /// A specific character that was expected
+    Char(char),
+    /// One of nom's own parsers failed
+    Nom(ErrorKind),
+}
+
+impl<I> ParseError<I> for VerboseError<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        Self {
+            errors: vec![(input, VerboseErrorKind::Nom(kind))],
+        }
+    }
+
+    fn append(input: I, kind: ErrorKind, mut other: Self) -> Self {
+        other.errors.push((input, VerboseErrorKind::Nom(kind)));
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        Self {
+            errors: vec![(input, VerboseErrorKind::Char(c))],
+        }
+    }
+}
+
+impl<I> ContextError<I> for VerboseError<I> {
+    fn add_context(input: I, ctx: &'static str, mut other: Self) -> Self {
+        other.errors.push((input, VerboseErrorKind::Context(ctx)));
+        other
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for VerboseError<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+        Self::from_error_kind(input, kind)
+    }
+}
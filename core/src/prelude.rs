@@ -1,7 +1,7 @@
 pub use crate::benchmark::benchmark_parts;
-pub use crate::coordinates::GridCoordinate;
+pub use crate::coordinates::{Grid, GridCoordinate, GridDelta};
 pub use crate::enums::{CompassDirection, GridDirection};
-pub use crate::error_handling::AdventError;
+pub use crate::error_handling::{AdventError, WithInput};
 pub use crate::input::get_input;
 pub use crate::timeouts::run_with_timeout;
 pub use crate::{error, invalid_coordinate, parse_error};
@@ -0,0 +1,451 @@
+use crate::coordinates::GridStorage;
+use crate::prelude::*;
+
+/// Which cells count as touching when growing a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// Only up, down, left and right
+    Four,
+    /// The four straight neighbours plus the diagonals
+    Eight,
+}
+
+impl Connectivity {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ],
+        }
+    }
+
+    /// The connectivity the cells outside of a region need to use, so that a diagonal gap can't
+    /// both join a region together and let the outside leak through it
+    fn complement(&self) -> Self {
+        match self {
+            Connectivity::Four => Connectivity::Eight,
+            Connectivity::Eight => Connectivity::Four,
+        }
+    }
+}
+
+/// A connected region of cells and its measurements
+///
+/// The perimeter and sides are measured along the edges of the cells, so a single cell has a
+/// perimeter of 4 and 4 sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The label of the region, which is also its index in `Regions::regions`
+    pub label: usize,
+    /// All the cells in the region, in the order they were found
+    pub cells: Vec<GridCoordinate<usize>>,
+    /// The number of cell edges between the region and anything outside of it
+    pub perimeter: usize,
+    /// The number of straight sides, which is always the same as the number of corners
+    pub sides: usize,
+    /// The top left corner of the bounding box
+    pub min: GridCoordinate<usize>,
+    /// The bottom right corner of the bounding box, inclusive
+    pub max: GridCoordinate<usize>,
+    connectivity: Connectivity,
+}
+
+/// The pockets of other cells that a region fully encloses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Holes {
+    /// The number of separate pockets
+    pub count: usize,
+    /// The total number of cells in them
+    pub area: usize,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn corners(&self) -> usize {
+        self.sides
+    }
+
+    /// Find the holes in the region, given the `labels` from the same `Regions`
+    ///
+    /// This flood fills the bounding box, so it's only worked out when asked for.
+    pub fn holes(&self, labels: &Grid<usize>) -> Holes {
+        count_holes(
+            labels,
+            self.label,
+            self.min,
+            self.max,
+            self.connectivity.complement(),
+        )
+    }
+}
+
+/// All the regions in a grid, along with a grid of which region each cell belongs to
+#[derive(Debug, Clone)]
+pub struct Regions {
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    /// The region a cell belongs to
+    pub fn region_at(&self, coord: &GridCoordinate<usize>) -> Option<&Region> {
+        self.labels.get(coord).map(|label| &self.regions[*label])
+    }
+}
+
+/// Split the whole grid into connected regions
+///
+/// Two neighbouring cells are in the same region when `same_region` returns true for them. For
+/// garden plots that's just comparing the plant type, but any equivalence works, such as
+/// "both are not walls".
+pub fn label_regions<G, F>(grid: &G, connectivity: Connectivity, mut same_region: F) -> Regions
+where
+    G: GridStorage + ?Sized,
+    F: FnMut(&G::Cell, &G::Cell) -> bool,
+{
+    let (height, width) = grid.dimensions();
+    let mut labels: Grid<Option<usize>> = Grid::new(height, width, None);
+    let mut regions: Vec<Vec<GridCoordinate<usize>>> = Vec::new();
+
+    for coord in labels.coordinates().collect::<Vec<_>>() {
+        if labels[coord].is_some() {
+            continue;
+        }
+
+        let label = regions.len();
+        labels[coord] = Some(label);
+        let mut cells = Vec::new();
+        let mut stack = vec![coord];
+
+        while let Some(current) = stack.pop() {
+            cells.push(current);
+            for (next_row, next_col) in
+                neighbours(current.row, current.column, height, width, connectivity)
+            {
+                let next = GridCoordinate::new(next_row, next_col);
+                if labels[next].is_none()
+                    && same_region(
+                        grid.cell(current.row, current.column),
+                        grid.cell(next_row, next_col),
+                    )
+                {
+                    labels[next] = Some(label);
+                    stack.push(next);
+                }
+            }
+        }
+
+        regions.push(cells);
+    }
+
+    // Every cell got a label in the loop above
+    let labels = labels.map(|label| label.unwrap());
+    let regions = regions
+        .into_iter()
+        .enumerate()
+        .map(|(label, cells)| measure_region(&labels, label, cells, connectivity))
+        .collect();
+
+    Regions { labels, regions }
+}
+
+/// Flood fill a single region from a starting cell, returning the cells in it
+pub fn flood_fill<G, F>(
+    grid: &G,
+    start: GridCoordinate<usize>,
+    connectivity: Connectivity,
+    mut same_region: F,
+) -> HashSet<GridCoordinate<usize>>
+where
+    G: GridStorage + ?Sized,
+    F: FnMut(&G::Cell, &G::Cell) -> bool,
+{
+    let (height, width) = grid.dimensions();
+    let mut seen = HashSet::new();
+    if start.row >= height || start.column >= width {
+        return seen;
+    }
+
+    seen.insert(start);
+    let mut stack = vec![start];
+
+    while let Some(current) = stack.pop() {
+        for (next_row, next_col) in
+            neighbours(current.row, current.column, height, width, connectivity)
+        {
+            let next = GridCoordinate::new(next_row, next_col);
+            if !seen.contains(&next)
+                && same_region(
+                    grid.cell(current.row, current.column),
+                    grid.cell(next_row, next_col),
+                )
+            {
+                seen.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+fn neighbours(
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+    connectivity: Connectivity,
+) -> impl Iterator<Item = (usize, usize)> {
+    connectivity
+        .offsets()
+        .iter()
+        .filter_map(move |(d_row, d_col)| {
+            let row = row.checked_add_signed(*d_row)?;
+            let col = col.checked_add_signed(*d_col)?;
+            (row < height && col < width).then_some((row, col))
+        })
+}
+
+fn measure_region(
+    labels: &Grid<usize>,
+    label: usize,
+    cells: Vec<GridCoordinate<usize>>,
+    connectivity: Connectivity,
+) -> Region {
+    let in_region = |row: usize, d_row: isize, col: usize, d_col: isize| -> bool {
+        match (row.checked_add_signed(d_row), col.checked_add_signed(d_col)) {
+            (Some(row), Some(col)) => labels.get(&GridCoordinate::new(row, col)) == Some(&label),
+            _ => false,
+        }
+    };
+
+    let mut perimeter = 0;
+    let mut corners = 0;
+    let mut min = cells[0];
+    let mut max = cells[0];
+
+    for cell in &cells {
+        let (row, col) = (cell.row, cell.column);
+
+        perimeter += Connectivity::Four
+            .offsets()
+            .iter()
+            .filter(|(d_row, d_col)| !in_region(row, *d_row, col, *d_col))
+            .count();
+
+        // Each corner of the cell is a corner of the region if it either sticks out (neither
+        // side is in the region) or is an inside corner (both sides are, but the diagonal isn't)
+        for (d_row, d_col) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+            let vertical = in_region(row, d_row, col, 0);
+            let horizontal = in_region(row, 0, col, d_col);
+            let diagonal = in_region(row, d_row, col, d_col);
+            if (!vertical && !horizontal) || (vertical && horizontal && !diagonal) {
+                corners += 1;
+            }
+        }
+
+        min.row = min.row.min(row);
+        min.column = min.column.min(col);
+        max.row = max.row.max(row);
+        max.column = max.column.max(col);
+    }
+
+    Region {
+        label,
+        cells,
+        perimeter,
+        sides: corners,
+        min,
+        max,
+        connectivity,
+    }
+}
+
+/// Count the pockets of other cells inside the bounding box that can't reach the outside
+fn count_holes(
+    labels: &Grid<usize>,
+    label: usize,
+    min: GridCoordinate<usize>,
+    max: GridCoordinate<usize>,
+    connectivity: Connectivity,
+) -> Holes {
+    // Work in a local grid with a one cell border around the bounding box, so everything outside
+    // of the region is connected through the border
+    let height = max.row - min.row + 3;
+    let width = max.column - min.column + 3;
+    let is_region = |row: usize, col: usize| -> bool {
+        row > 0
+            && col > 0
+            && row < height - 1
+            && col < width - 1
+            && labels[GridCoordinate::new(min.row + row - 1, min.column + col - 1)] == label
+    };
+
+    let mut seen: Grid<bool> = Grid::new(height, width, false);
+    let fill = |start: GridCoordinate<usize>, seen: &mut Grid<bool>| -> usize {
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut size = 0;
+        while let Some(current) = stack.pop() {
+            size += 1;
+            for (row, col) in neighbours(current.row, current.column, height, width, connectivity) {
+                let next = GridCoordinate::new(row, col);
+                if !seen[next] && !is_region(row, col) {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        size
+    };
+
+    // The corner is always part of the border, so this marks everything outside
+    fill(GridCoordinate::new(0, 0), &mut seen);
+
+    let mut holes = Holes { count: 0, area: 0 };
+    for row in 1..height - 1 {
+        for col in 1..width - 1 {
+            let coord = GridCoordinate::new(row, col);
+            if !seen[coord] && !is_region(row, col) {
+                holes.count += 1;
+                holes.area += fill(coord, &mut seen);
+            }
+        }
+    }
+
+    holes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Grid<char> {
+        Grid::parse(input, Some).unwrap()
+    }
+
+    #[test]
+    fn test_garden_plots() {
+        let grid = parse("AAAA\nBBCD\nBBCC\nEEEC");
+        let regions = label_regions(&grid, Connectivity::Four, |a, b| a == b);
+
+        assert_eq!(regions.regions.len(), 5);
+
+        let measurements: Vec<_> = regions
+            .regions
+            .iter()
+            .map(|region| (region.area(), region.perimeter, region.sides))
+            .collect();
+        assert_eq!(
+            measurements,
+            vec![(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let grid = vec![vec!['A', 'A'], vec!['B', 'A']];
+        let regions = label_regions(&grid, Connectivity::Four, |a, b| a == b);
+
+        assert_eq!(regions.labels.row(0), Some(&[0, 0][..]));
+        assert_eq!(regions.labels.row(1), Some(&[1, 0][..]));
+        let region = regions.region_at(&GridCoordinate::new(1, 1)).unwrap();
+        assert_eq!(region.label, 0);
+        assert_eq!(region.area(), 3);
+    }
+
+    #[test]
+    fn test_holes() {
+        let grid = parse("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+        let regions = label_regions(&grid, Connectivity::Four, |a, b| a == b);
+
+        let outer = &regions.regions[0];
+        assert_eq!(outer.area(), 21);
+        assert_eq!(outer.holes(&regions.labels), Holes { count: 4, area: 4 });
+        assert_eq!(outer.perimeter, 36);
+        // 4 outside sides, and 4 sides around each of the holes
+        assert_eq!(outer.sides, 20);
+        assert_eq!(outer.min, GridCoordinate::new(0, 0));
+        assert_eq!(outer.max, GridCoordinate::new(4, 4));
+    }
+
+    #[test]
+    fn test_diagonal_touching_regions() {
+        let grid = parse("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA");
+
+        let four = label_regions(&grid, Connectivity::Four, |a, b| a == b);
+        assert_eq!(four.regions.len(), 3);
+        assert_eq!(four.regions[0].area(), 28);
+        assert_eq!(four.regions[0].sides, 12);
+        // The B blocks touch diagonally, which is enough to make them a single hole in A
+        assert_eq!(
+            four.regions[0].holes(&four.labels),
+            Holes { count: 1, area: 8 }
+        );
+
+        let eight = label_regions(&grid, Connectivity::Eight, |a, b| a == b);
+        assert_eq!(eight.regions.len(), 2);
+        // But with A squeezing through diagonally, they're two separate holes
+        assert_eq!(eight.regions[0].holes(&eight.labels).count, 2);
+        assert_eq!(eight.regions[1].area(), 8);
+        assert_eq!(eight.regions[1].perimeter, 16);
+        assert_eq!(eight.regions[1].min, GridCoordinate::new(1, 1));
+        assert_eq!(eight.regions[1].max, GridCoordinate::new(4, 4));
+    }
+
+    #[test]
+    fn test_eight_connected_ring_has_a_hole() {
+        let grid = parse(".#.\n#.#\n.#.");
+
+        let four = label_regions(&grid, Connectivity::Four, |a, b| a == b);
+        assert!(four
+            .regions
+            .iter()
+            .all(|region| region.holes(&four.labels).count == 0));
+
+        let eight = label_regions(&grid, Connectivity::Eight, |a, b| a == b);
+        let ring = eight.region_at(&GridCoordinate::new(0, 1)).unwrap();
+        assert_eq!(ring.area(), 4);
+        assert_eq!(ring.holes(&eight.labels), Holes { count: 1, area: 1 });
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = parse("..#..\n..#..\n#####\n.....");
+
+        let area = flood_fill(
+            &grid,
+            GridCoordinate::new(0, 0),
+            Connectivity::Four,
+            |a, b| a == b,
+        );
+        assert_eq!(area.len(), 4);
+
+        let walls = flood_fill(
+            &grid,
+            GridCoordinate::new(2, 2),
+            Connectivity::Eight,
+            |a, b| a == b,
+        );
+        assert_eq!(walls.len(), 7);
+
+        assert!(flood_fill(
+            &grid,
+            GridCoordinate::new(9, 9),
+            Connectivity::Four,
+            |_, _| true
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,644 @@
+use crate::prelude::*;
+use num_traits::Zero;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The outcome of a shortest path search
+///
+/// Even if no goal was reached, `distances` still holds the shortest distance to every state the
+/// search settled, so a search with a goal that never matches doubles as a flood fill.
+///
+/// Searches started with one of the `_all_paths` variants additionally record every predecessor
+/// that reaches a state at its optimal cost, which is what the `optimal_` methods walk through.
+/// Without it, those methods only see the single path the search settled on.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    /// The first goal state that was reached, if any
+    pub goal: Option<S>,
+    /// The shortest distance to every state that was settled during the search
+    pub distances: HashMap<S, C>,
+    predecessors: HashMap<S, S>,
+    /// Every goal state reached at the optimal cost, only filled in past the first one when
+    /// recording all paths
+    goals: Vec<S>,
+    all_predecessors: Option<HashMap<S, Vec<S>>>,
+}
+
+impl<S, C> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn new(record_all_paths: bool) -> Self {
+        Self {
+            goal: None,
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+            goals: Vec::new(),
+            all_predecessors: record_all_paths.then(HashMap::new),
+        }
+    }
+
+    /// The cost of the path to the goal
+    pub fn cost(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    /// The shortest distance to any settled state
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    /// The path from a start state to the goal, both ends included
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+
+    /// The path from a start state to any settled state, both ends included
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        let mut current = state;
+        while let Some(previous) = self.predecessors.get(current) {
+            path.push(previous.clone());
+            current = previous;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Every goal state that was reached at the optimal cost
+    pub fn goals(&self) -> &[S] {
+        &self.goals
+    }
+
+    /// The states that reach the given state at its optimal cost, in the order they were found
+    pub fn optimal_predecessors(&self, state: &S) -> &[S] {
+        match &self.all_predecessors {
+            Some(all) => all.get(state).map_or(&[], Vec::as_slice),
+            None => self
+                .predecessors
+                .get(state)
+                .map_or(&[], std::slice::from_ref),
+        }
+    }
+
+    /// The union of all the states on any optimal path to any of the goals
+    pub fn optimal_path_states(&self) -> HashSet<S> {
+        let mut seen: HashSet<S> = HashSet::new();
+        let mut stack: Vec<&S> = self.goals.iter().collect();
+
+        while let Some(state) = stack.pop() {
+            if seen.insert(state.clone()) {
+                stack.extend(self.optimal_predecessors(state));
+            }
+        }
+
+        seen
+    }
+
+    /// The number of distinct optimal paths from any start to any of the goals
+    ///
+    /// This is counted over the predecessor graph, so the paths are never built up.
+    pub fn optimal_path_count(&self) -> usize {
+        let mut counts: HashMap<&S, usize> = HashMap::new();
+        self.goals
+            .iter()
+            .map(|goal| self.count_paths_to(goal, &mut counts))
+            .sum()
+    }
+
+    /// Paths can be far longer than the call stack allows, so this walks the predecessors with a
+    /// stack of its own. A state is only counted once all of its predecessors have been.
+    fn count_paths_to<'a>(&'a self, goal: &'a S, counts: &mut HashMap<&'a S, usize>) -> usize {
+        let mut stack = vec![goal];
+        while let Some(&state) = stack.last() {
+            if counts.contains_key(state) {
+                stack.pop();
+                continue;
+            }
+
+            let predecessors = self.optimal_predecessors(state);
+            let waiting = stack.len();
+            stack.extend(
+                predecessors
+                    .iter()
+                    .filter(|previous| !counts.contains_key(previous)),
+            );
+            if stack.len() == waiting {
+                // Nothing leads to a start state, otherwise every way in is already counted
+                let count = match predecessors {
+                    [] => 1,
+                    _ => predecessors.iter().map(|previous| counts[previous]).sum(),
+                };
+                counts.insert(state, count);
+                stack.pop();
+            }
+        }
+
+        counts[goal]
+    }
+
+    /// Every distinct optimal path from any start to any of the goals, both ends included
+    ///
+    /// The number of paths can grow exponentially, so prefer `optimal_path_states` or
+    /// `optimal_path_count` when those are all that is needed.
+    pub fn optimal_paths(&self) -> Vec<Vec<S>> {
+        let mut paths = Vec::new();
+        for goal in &self.goals {
+            // Walk backwards from the goal, with `path` holding the reversed path so far and each
+            // stack entry remembering how much of it leads up to that state
+            let mut path: Vec<S> = Vec::new();
+            let mut stack = vec![(goal, 0)];
+            while let Some((state, depth)) = stack.pop() {
+                path.truncate(depth);
+                path.push(state.clone());
+
+                let predecessors = self.optimal_predecessors(state);
+                if predecessors.is_empty() {
+                    paths.push(path.iter().rev().cloned().collect());
+                }
+                stack.extend(
+                    predecessors
+                        .iter()
+                        .rev()
+                        .map(|previous| (previous, depth + 1)),
+                );
+            }
+        }
+
+        paths
+    }
+}
+
+/// Breadth first search where every step costs one
+///
+/// The search stops as soon as a state matching `is_goal` is reached. Multiple start states are
+/// all at distance zero.
+pub fn bfs<S, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    mut is_goal: G,
+) -> SearchResult<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut result = SearchResult::new(false);
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            result.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            result.goals.push(state.clone());
+            result.goal = Some(state);
+            break;
+        }
+
+        let distance = result.distances[&state];
+        for next in neighbours(&state) {
+            if result.distances.contains_key(&next) {
+                continue;
+            }
+            result.distances.insert(next.clone(), distance + 1);
+            result.predecessors.insert(next.clone(), state.clone());
+            queue.push_back(next);
+        }
+    }
+
+    result
+}
+
+/// Dijkstra's algorithm, with the neighbours closure returning each next state along with the
+/// cost of the step to get there
+pub fn dijkstra<S, C, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    is_goal: G,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    best_first(starts, neighbours, |_| C::zero(), is_goal, false)
+}
+
+/// Dijkstra's algorithm that records every optimal predecessor of each state, for when all the
+/// shortest paths are needed and not just one of them
+///
+/// The search carries on until every goal reachable at the optimal cost has been found. Step
+/// costs need to be positive, as zero cost steps could make the predecessors loop.
+pub fn dijkstra_all_paths<S, C, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    is_goal: G,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    best_first(starts, neighbours, |_| C::zero(), is_goal, true)
+}
+
+/// A* search, which is Dijkstra's algorithm guided by a heuristic estimate of the remaining cost
+///
+/// The heuristic needs to never overestimate the remaining cost (and not drop by more than the
+/// step cost between neighbours) for the result to be the shortest path. The manhattan distance
+/// to the goal is the usual choice on a grid.
+pub fn astar<S, C, N, I, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    heuristic: H,
+    is_goal: G,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    best_first(starts, neighbours, heuristic, is_goal, false)
+}
+
+/// A* search that records every optimal predecessor, see `dijkstra_all_paths`
+pub fn astar_all_paths<S, C, N, I, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    heuristic: H,
+    is_goal: G,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    best_first(starts, neighbours, heuristic, is_goal, true)
+}
+
+fn best_first<S, C, N, I, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    mut heuristic: H,
+    mut is_goal: G,
+    record_all_paths: bool,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut result = SearchResult::new(record_all_paths);
+    // The best known cost to reach each state, which is only final once the state is popped
+    let mut best: HashMap<S, C> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    let mut goal_cost = None;
+
+    for start in starts {
+        if !best.contains_key(&start) {
+            best.insert(start.clone(), C::zero());
+            heap.push(QueueEntry {
+                priority: heuristic(&start),
+                cost: C::zero(),
+                state: start,
+            });
+        }
+    }
+
+    while let Some(QueueEntry {
+        priority,
+        cost,
+        state,
+    }) = heap.pop()
+    {
+        if goal_cost.is_some_and(|goal_cost| priority > goal_cost) {
+            // Only reachable when recording all paths, everything left is worse than the goal
+            break;
+        }
+        if result.distances.contains_key(&state) || best.get(&state) != Some(&cost) {
+            // Already settled, or a stale entry that was improved on after it was queued
+            continue;
+        }
+        result.distances.insert(state.clone(), cost);
+
+        if is_goal(&state) {
+            result.goals.push(state.clone());
+            if result.goal.is_none() {
+                result.goal = Some(state);
+            }
+            if !record_all_paths {
+                break;
+            }
+            goal_cost = Some(cost);
+            continue;
+        }
+
+        for (next, step_cost) in neighbours(&state) {
+            let next_cost = cost + step_cost;
+            match best.get(&next) {
+                Some(known) if *known < next_cost => continue,
+                Some(known) if *known == next_cost => {
+                    // Another way of getting to the same state at the same cost
+                    if let Some(all) = result.all_predecessors.as_mut() {
+                        all.entry(next).or_default().push(state.clone());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            best.insert(next.clone(), next_cost);
+            result.predecessors.insert(next.clone(), state.clone());
+            if let Some(all) = result.all_predecessors.as_mut() {
+                all.insert(next.clone(), vec![state.clone()]);
+            }
+            heap.push(QueueEntry {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                state: next,
+            });
+        }
+    }
+
+    result
+}
+
+/// An entry in the priority queue, ordered so that the `BinaryHeap` pops the lowest priority
+/// first without having to negate the costs
+struct QueueEntry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for QueueEntry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for QueueEntry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for QueueEntry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for QueueEntry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to pop the lowest priority, preferring the highest cost on ties since that
+        // entry is the furthest along towards the goal
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn parse_maze(input: &str) -> (Grid<char>, GridCoordinate<i32>, GridCoordinate<i32>) {
+        let grid = Grid::parse(input, Some).unwrap();
+        let start = grid.find(&'S').unwrap().cast().unwrap();
+        let end = grid.find(&'E').unwrap().cast().unwrap();
+        (grid, start, end)
+    }
+
+    fn open_neighbours(grid: &Grid<char>, coord: &GridCoordinate<i32>) -> Vec<GridCoordinate<i32>> {
+        coord
+            .edge_coordinates(1)
+            .filter(|next| grid.get(next).is_some_and(|c| *c != '#'))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start, end) = parse_maze(MAZE);
+
+        let result = bfs(
+            [start],
+            |coord| open_neighbours(&grid, coord),
+            |c| *c == end,
+        );
+
+        assert_eq!(result.cost(), Some(15));
+        let path = result.path().unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        for step in path.windows(2) {
+            assert_eq!(step[0].manhattan_distance(&step[1]), 1);
+        }
+    }
+
+    #[test]
+    fn test_bfs_unreachable_fills_distances() {
+        let (grid, start, _) = parse_maze(MAZE);
+
+        let result = bfs([start], |coord| open_neighbours(&grid, coord), |_| false);
+
+        assert_eq!(result.cost(), None);
+        assert_eq!(result.path(), None);
+        assert_eq!(
+            result.distances.len(),
+            grid.positions(|c| *c != '#').count()
+        );
+        assert_eq!(result.distance(&GridCoordinate::new(0, 2)), Some(2));
+    }
+
+    #[test]
+    fn test_bfs_multiple_goals() {
+        let (grid, start, end) = parse_maze(MAZE);
+        let goals = [end, GridCoordinate::new(0, 4)];
+
+        let result = bfs(
+            [start],
+            |coord| open_neighbours(&grid, coord),
+            |c| goals.contains(c),
+        );
+
+        assert_eq!(result.goal, Some(GridCoordinate::new(0, 4)));
+        assert_eq!(result.cost(), Some(8));
+    }
+
+    #[test]
+    fn test_dijkstra_weighted() {
+        // A small graph where the direct edge is more expensive than going around
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from_iter([
+            ('a', vec![('b', 7), ('c', 9), ('f', 14)]),
+            ('b', vec![('a', 7), ('c', 10), ('d', 15)]),
+            ('c', vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)]),
+            ('d', vec![('b', 15), ('c', 11), ('e', 6)]),
+            ('e', vec![('d', 6), ('f', 9)]),
+            ('f', vec![('a', 14), ('c', 2), ('e', 9)]),
+        ]);
+
+        let result = dijkstra(['a'], |node| edges[node].clone(), |node| *node == 'e');
+
+        assert_eq!(result.cost(), Some(20));
+        assert_eq!(result.path(), Some(vec!['a', 'c', 'f', 'e']));
+        assert_eq!(result.distance(&'c'), Some(9));
+    }
+
+    #[test]
+    fn test_dijkstra_multiple_starts() {
+        let result = dijkstra([0i32, 10], |n| vec![(n + 1, 1u32), (n - 1, 1)], |n| *n == 7);
+
+        assert_eq!(result.cost(), Some(3));
+        assert_eq!(result.path(), Some(vec![10, 9, 8, 7]));
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let (grid, start, end) = parse_maze(MAZE);
+        let neighbours = |coord: &GridCoordinate<i32>| {
+            open_neighbours(&grid, coord)
+                .into_iter()
+                .map(|next| (next, 1))
+                .collect::<Vec<_>>()
+        };
+
+        let with_heuristic = astar(
+            [start],
+            neighbours,
+            |coord| coord.manhattan_distance(&end),
+            |c| *c == end,
+        );
+        let without_heuristic = dijkstra([start], neighbours, |c| *c == end);
+
+        assert_eq!(with_heuristic.cost(), Some(15));
+        assert_eq!(with_heuristic.cost(), without_heuristic.cost());
+        assert!(with_heuristic.distances.len() <= without_heuristic.distances.len());
+    }
+
+    fn open_grid_neighbours(
+        size: i32,
+    ) -> impl FnMut(&GridCoordinate<i32>) -> Vec<(GridCoordinate<i32>, u32)> {
+        move |coord| {
+            coord
+                .edge_coordinates(1)
+                .filter(|next| {
+                    next.row >= 0 && next.column >= 0 && next.row < size && next.column < size
+                })
+                .map(|next| (next, 1))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_all_paths_open_grid() {
+        let start = GridCoordinate::new(0, 0);
+        let end = GridCoordinate::new(2, 2);
+
+        let result = dijkstra_all_paths([start], open_grid_neighbours(3), |c| *c == end);
+
+        assert_eq!(result.cost(), Some(4));
+        // Choosing 2 of the 4 steps to go down
+        assert_eq!(result.optimal_path_count(), 6);
+        assert_eq!(result.optimal_paths().len(), 6);
+        assert_eq!(result.optimal_path_states().len(), 9);
+        assert_eq!(result.optimal_predecessors(&end).len(), 2);
+        for path in result.optimal_paths() {
+            assert_eq!(path.len(), 5);
+            assert_eq!(path.first(), Some(&start));
+            assert_eq!(path.last(), Some(&end));
+        }
+    }
+
+    #[test]
+    fn test_all_paths_maze() {
+        let (grid, start, end) = parse_maze(MAZE);
+        let neighbours = |coord: &GridCoordinate<i32>| {
+            open_neighbours(&grid, coord)
+                .into_iter()
+                .map(|next| (next, 1))
+                .collect::<Vec<_>>()
+        };
+
+        let result = dijkstra_all_paths([start], neighbours, |c| *c == end);
+
+        // There's only the one way through the maze
+        assert_eq!(result.cost(), Some(15));
+        assert_eq!(result.optimal_path_count(), 1);
+        assert_eq!(result.optimal_paths(), vec![result.path().unwrap()]);
+    }
+
+    #[test]
+    fn test_all_paths_multiple_goals() {
+        let start = GridCoordinate::new(1, 1);
+        let goals = [GridCoordinate::new(0, 0), GridCoordinate::new(2, 2)];
+
+        let result = astar_all_paths(
+            [start],
+            open_grid_neighbours(3),
+            |_| 0,
+            |c| goals.contains(c),
+        );
+
+        assert_eq!(result.cost(), Some(2));
+        assert_eq!(result.goals().len(), 2);
+        assert_eq!(result.optimal_path_count(), 4);
+        assert_eq!(result.optimal_path_states().len(), 7);
+    }
+
+    #[test]
+    fn test_single_path_without_recording() {
+        let start = GridCoordinate::new(0, 0);
+        let end = GridCoordinate::new(2, 2);
+
+        let result = dijkstra([start], open_grid_neighbours(3), |c| *c == end);
+
+        assert_eq!(result.cost(), Some(4));
+        assert_eq!(result.optimal_path_count(), 1);
+        assert_eq!(result.optimal_paths(), vec![result.path().unwrap()]);
+    }
+
+    #[test]
+    fn test_long_paths() {
+        // Far more steps than there would be room for stack frames
+        let length = 200_000;
+        let result = dijkstra_all_paths(
+            [0usize],
+            |&n| (n < length).then_some((n + 1, 1)),
+            |&n| n == length,
+        );
+
+        assert_eq!(result.optimal_path_count(), 1);
+        assert_eq!(result.optimal_paths()[0].len(), length + 1);
+    }
+}
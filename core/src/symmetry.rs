@@ -0,0 +1,201 @@
+use crate::coordinates::{Grid, GridStorage};
+
+/// A line that a sequence (or grid) might be mirrored around
+///
+/// The axis sits just before the item at `position`, so `position` is also the number of items
+/// on the near side of it. `mismatches` counts the pairs of mirrored items that don't match, up
+/// to the nearer edge, so a perfect reflection has none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mirror {
+    pub position: usize,
+    pub mismatches: usize,
+}
+
+impl Mirror {
+    pub fn is_perfect(&self) -> bool {
+        self.mismatches == 0
+    }
+}
+
+/// Every mirror axis in the sequence, where each differing pair of items counts as one mismatch
+pub fn mirrors<T: PartialEq>(items: &[T]) -> Vec<Mirror> {
+    mirrors_by(items, |a, b| usize::from(a != b))
+}
+
+/// Every mirror axis in the sequence, with `mismatches` saying how far apart two items are
+///
+/// This is for when each item stands for several cells, like a row packed into bits, where the
+/// count of differing bits is what matters.
+pub fn mirrors_by<T, F>(items: &[T], mismatches: F) -> Vec<Mirror>
+where
+    F: Fn(&T, &T) -> usize,
+{
+    axes(items.len(), |a, b| mismatches(&items[a], &items[b]))
+}
+
+/// Every axis between two rows of the grid, counting each differing cell as a mismatch
+pub fn horizontal_mirrors<T: PartialEq>(grid: &Grid<T>) -> Vec<Mirror> {
+    let width = grid.width();
+    axes(grid.height(), |a, b| {
+        (0..width)
+            .filter(|&col| grid.cell(a, col) != grid.cell(b, col))
+            .count()
+    })
+}
+
+/// Every axis between two columns of the grid, counting each differing cell as a mismatch
+pub fn vertical_mirrors<T: PartialEq>(grid: &Grid<T>) -> Vec<Mirror> {
+    let height = grid.height();
+    axes(grid.width(), |a, b| {
+        (0..height)
+            .filter(|&row| grid.cell(row, a) != grid.cell(row, b))
+            .count()
+    })
+}
+
+/// How many cells differ from the cell they'd land on after turning the grid by quarter turns
+///
+/// A quarter or three quarter turn only makes sense for a square grid, so that's `None` for any
+/// other shape. Every cell is compared, so each mismatched pair is counted from both ends.
+pub fn rotational_mismatches<T: PartialEq>(grid: &Grid<T>, quarter_turns: usize) -> Option<usize> {
+    let (height, width) = grid.dimensions();
+    let turned = |row: usize, col: usize| match quarter_turns % 4 {
+        0 => (row, col),
+        1 => (col, height - 1 - row),
+        2 => (height - 1 - row, width - 1 - col),
+        _ => (width - 1 - col, row),
+    };
+    if quarter_turns % 2 == 1 && height != width {
+        return None;
+    }
+
+    Some(
+        grid.iter()
+            .filter(|(coord, cell)| {
+                let (row, col) = turned(coord.row, coord.column);
+                *cell != grid.cell(row, col)
+            })
+            .count(),
+    )
+}
+
+/// Every shift that maps the sequence onto itself, smallest first
+///
+/// A shift counts when every item matches the one that many places further on. The length of
+/// the sequence is always one, as nothing overlaps.
+pub fn periods<T: PartialEq>(items: &[T]) -> Vec<usize> {
+    shifts(items.len(), |a, b| items[a] == items[b])
+}
+
+/// Every number of rows the grid can be moved down by and still match where it overlaps
+pub fn row_periods<T: PartialEq>(grid: &Grid<T>) -> Vec<usize> {
+    shifts(grid.height(), |a, b| grid.row(a) == grid.row(b))
+}
+
+/// Every number of columns the grid can be moved across by and still match where it overlaps
+pub fn column_periods<T: PartialEq>(grid: &Grid<T>) -> Vec<usize> {
+    let height = grid.height();
+    shifts(grid.width(), |a, b| {
+        (0..height).all(|row| grid.cell(row, a) == grid.cell(row, b))
+    })
+}
+
+/// Check each axis between `0..len` by pairing off the indices either side of it
+fn axes<F>(len: usize, mismatches: F) -> Vec<Mirror>
+where
+    F: Fn(usize, usize) -> usize,
+{
+    (1..len)
+        .map(|position| Mirror {
+            position,
+            mismatches: (0..position.min(len - position))
+                .map(|offset| mismatches(position - 1 - offset, position + offset))
+                .sum(),
+        })
+        .collect()
+}
+
+fn shifts<F>(len: usize, same: F) -> Vec<usize>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    (1..=len)
+        .filter(|&shift| (0..len - shift).all(|idx| same(idx, idx + shift)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(s: &str) -> Grid<char> {
+        Grid::parse(s, Some).unwrap()
+    }
+
+    #[test]
+    fn test_mirrors() {
+        let found = mirrors(&[1, 2, 2, 1, 5]);
+
+        assert_eq!(found.len(), 4);
+        assert_eq!(found.iter().find(|m| m.is_perfect()).unwrap().position, 2);
+        assert_eq!(
+            found[2],
+            Mirror {
+                position: 3,
+                mismatches: 2
+            }
+        );
+
+        let bits = mirrors_by(&[0b101u32, 0b100, 0b111], |a, b| {
+            (a ^ b).count_ones() as usize
+        });
+        assert_eq!(bits[0].mismatches, 1);
+        assert_eq!(bits[1].mismatches, 2);
+    }
+
+    #[test]
+    fn test_grid_mirrors() {
+        // The first example pattern from 2023 day 13
+        let pattern =
+            grid("#.##..##.\n..#.##.#.\n##......#\n##......#\n..#.##.#.\n..##..##.\n#.#.##.#.");
+
+        let perfect: Vec<usize> = vertical_mirrors(&pattern)
+            .into_iter()
+            .filter(Mirror::is_perfect)
+            .map(|m| m.position)
+            .collect();
+        assert_eq!(perfect, vec![5]);
+
+        // Fixing the smudge gives a horizontal line instead
+        let smudged = horizontal_mirrors(&pattern)
+            .into_iter()
+            .find(|m| m.mismatches == 1)
+            .unwrap();
+        assert_eq!(smudged.position, 3);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let pinwheel = grid("#..\n...\n..#");
+        assert_eq!(rotational_mismatches(&pinwheel, 2), Some(0));
+        assert_eq!(rotational_mismatches(&pinwheel, 1), Some(4));
+        assert_eq!(rotational_mismatches(&pinwheel, 4), Some(0));
+
+        let cross = grid(".#.\n###\n.#.");
+        assert_eq!(rotational_mismatches(&cross, 1), Some(0));
+        assert_eq!(rotational_mismatches(&cross, 3), Some(0));
+
+        assert_eq!(rotational_mismatches(&grid("ab"), 1), None);
+        assert_eq!(rotational_mismatches(&grid("ab"), 2), Some(2));
+    }
+
+    #[test]
+    fn test_periods() {
+        assert_eq!(periods(&[1, 2, 1, 2, 1]), vec![2, 4, 5]);
+        assert_eq!(periods::<u8>(&[]), Vec::<usize>::new());
+
+        let tiles = grid("ab.ab\ncd.cd\nab.ab");
+        assert_eq!(row_periods(&tiles), vec![2, 3]);
+        assert_eq!(column_periods(&tiles), vec![3, 5]);
+    }
+}
@@ -1,12 +1,16 @@
+use crate::error_handling::WithInput;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// Run a part, warning if it takes a while
+///
+/// Parse failures in the result are pointed at the line and column of the input they came from.
 pub fn run_with_timeout<F, T>(part_name: &str, f: F, input: &str) -> T
 where
     F: FnOnce(&str) -> T,
-    T: Send + 'static,
+    T: WithInput + Send + 'static,
 {
     let input = input.to_string();
     let part_name = part_name.to_string();
@@ -40,5 +44,5 @@ where
 
     let _ = monitor.join();
 
-    result
+    result.with_input(&input)
 }
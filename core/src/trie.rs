@@ -0,0 +1,222 @@
+use crate::prelude::*;
+use std::hash::Hash;
+
+/// A prefix tree of words made of any kind of symbol
+///
+/// All the nodes live in one `Vec`, with each one pointing at its children by index, so there's no
+/// boxing and the whole thing can be dropped in one go.
+#[derive(Debug, Clone)]
+pub struct Trie<S> {
+    nodes: Vec<TrieNode<S>>,
+    words: usize,
+}
+
+#[derive(Debug, Clone)]
+struct TrieNode<S> {
+    children: HashMap<S, usize>,
+    /// Whether a word ends at this node
+    terminal: bool,
+}
+
+impl<S> TrieNode<S> {
+    fn new() -> Self {
+        Self {
+            children: HashMap::new(),
+            terminal: false,
+        }
+    }
+}
+
+impl<S: Eq + Hash + Clone> Trie<S> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![TrieNode::new()],
+            words: 0,
+        }
+    }
+
+    /// The number of distinct words in the trie
+    pub fn len(&self) -> usize {
+        self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+
+    /// Add a word, returning false if it was already there
+    pub fn insert(&mut self, word: impl IntoIterator<Item = S>) -> bool {
+        let mut current = 0;
+        for symbol in word {
+            current = match self.nodes[current].children.get(&symbol) {
+                Some(child) => *child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::new());
+                    self.nodes[current].children.insert(symbol, child);
+                    child
+                }
+            };
+        }
+
+        let added = !self.nodes[current].terminal;
+        self.nodes[current].terminal = true;
+        self.words += usize::from(added);
+        added
+    }
+
+    /// Whether the exact word is in the trie
+    pub fn contains(&self, word: &[S]) -> bool {
+        self.walk(word)
+            .is_some_and(|node| self.nodes[node].terminal)
+    }
+
+    /// Whether any word in the trie starts with the prefix
+    pub fn starts_with(&self, prefix: &[S]) -> bool {
+        self.walk(prefix).is_some()
+    }
+
+    /// The lengths of every word that the sequence starts with, shortest first
+    pub fn prefix_lengths<'a>(&'a self, sequence: &'a [S]) -> impl Iterator<Item = usize> + 'a {
+        let mut current = 0;
+        sequence
+            .iter()
+            .enumerate()
+            .map_while(move |(idx, symbol)| {
+                current = *self.nodes[current].children.get(symbol)?;
+                Some((idx + 1, self.nodes[current].terminal))
+            })
+            .filter_map(|(length, terminal)| terminal.then_some(length))
+    }
+
+    /// The length of the longest word that the sequence starts with
+    ///
+    /// Only the empty word matches nothing, so that's `Some(0)` if it's been inserted.
+    pub fn longest_match(&self, sequence: &[S]) -> Option<usize> {
+        self.prefix_lengths(sequence)
+            .last()
+            .or(self.nodes[0].terminal.then_some(0))
+    }
+
+    /// How many different ways the sequence can be split up into words from the trie
+    ///
+    /// Works back from the end, counting the ways to finish from each position, so every position
+    /// is only ever worked out once. The empty word is ignored, as it would make the count
+    /// infinite.
+    pub fn count_tilings(&self, sequence: &[S]) -> usize {
+        let mut ways = vec![0; sequence.len() + 1];
+        ways[sequence.len()] = 1;
+
+        for start in (0..sequence.len()).rev() {
+            ways[start] = self
+                .prefix_lengths(&sequence[start..])
+                .map(|length| ways[start + length])
+                .sum();
+        }
+
+        ways[0]
+    }
+
+    /// Whether the sequence can be split up into words from the trie at all
+    pub fn can_tile(&self, sequence: &[S]) -> bool {
+        let mut reachable = vec![false; sequence.len() + 1];
+        reachable[0] = true;
+
+        for start in 0..sequence.len() {
+            if !reachable[start] {
+                continue;
+            }
+            for length in self.prefix_lengths(&sequence[start..]) {
+                reachable[start + length] = true;
+            }
+        }
+
+        reachable[sequence.len()]
+    }
+
+    /// The node at the end of the prefix, if there is one
+    fn walk(&self, prefix: &[S]) -> Option<usize> {
+        prefix.iter().try_fold(0, |node, symbol| {
+            self.nodes[node].children.get(symbol).copied()
+        })
+    }
+}
+
+impl<S: Eq + Hash + Clone> Default for Trie<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Eq + Hash + Clone, W: IntoIterator<Item = S>> FromIterator<W> for Trie<S> {
+    fn from_iter<I: IntoIterator<Item = W>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for word in iter {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The towels from 2024 day 19
+    fn towels() -> Trie<char> {
+        ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]
+            .iter()
+            .map(|towel| towel.chars())
+            .collect()
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut trie = towels();
+
+        assert_eq!(trie.len(), 8);
+        assert!(trie.contains(&chars("bwu")));
+        assert!(!trie.contains(&chars("bw")));
+        assert!(trie.starts_with(&chars("bw")));
+        assert!(!trie.starts_with(&chars("u")));
+
+        assert!(!trie.insert("rb".chars()));
+        assert!(trie.insert("bw".chars()));
+        assert_eq!(trie.len(), 9);
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let trie = towels();
+
+        assert_eq!(
+            trie.prefix_lengths(&chars("bwurrg")).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(trie.longest_match(&chars("bwurrg")), Some(3));
+        assert_eq!(trie.longest_match(&chars("ubwu")), None);
+    }
+
+    #[test]
+    fn test_count_tilings() {
+        let trie = towels();
+
+        for (design, ways) in [
+            ("brwrr", 2),
+            ("bggr", 1),
+            ("gbbr", 4),
+            ("rrbgbr", 6),
+            ("ubwu", 0),
+            ("bwurrg", 1),
+            ("brgr", 2),
+            ("bbrgwb", 0),
+        ] {
+            assert_eq!(trie.count_tilings(&chars(design)), ways, "{}", design);
+            assert_eq!(trie.can_tile(&chars(design)), ways > 0, "{}", design);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use advent::ocr;
 use advent::prelude::*;
 use cpu::{Instruction, CPU};
 use itertools::Itertools;
@@ -52,6 +53,11 @@ fn part1(input: &str) -> Result<i32> {
 }
 
 fn part2(input: &str) -> Result<String> {
+    ocr::recognise(&render_screen(input)?)
+}
+
+/// Run the program, drawing the CRT as 6 lines of 40 `#` and `.` characters
+fn render_screen(input: &str) -> Result<String> {
     let mut cpu = CPU::new();
     let instructions = parse_instructions(input)?;
 
@@ -98,8 +104,8 @@ mod tests {
     }
 
     #[test]
-    fn test_part2() {
-        let result = part2(TEST_INPUT).unwrap();
+    fn test_render_screen() {
+        let result = render_screen(TEST_INPUT).unwrap();
         let expected = [
             "##..##..##..##..##..##..##..##..##..##..",
             "###...###...###...###...###...###...###.",
@@ -111,4 +117,57 @@ mod tests {
         .join("\n");
         assert_eq!(result, expected);
     }
+
+    /// Write a program that draws `screen`
+    ///
+    /// Every instruction is an `addx`, so each one covers a pair of pixels on the same line. The
+    /// sprite is placed for each pair so it covers exactly the pixels that should be lit, and each
+    /// `addx` moves it on to where the next pair needs it.
+    fn program_for(screen: &str) -> String {
+        let pixels: Vec<bool> = screen
+            .lines()
+            .flat_map(|line| line.chars().map(|c| c == '#'))
+            .collect();
+        let positions: Vec<i32> = pixels
+            .chunks(2)
+            .enumerate()
+            .map(|(pair, lit)| {
+                let column = (pair * 2 % 40) as i32;
+                match (lit[0], lit[1]) {
+                    (true, true) => column + 1,
+                    (true, false) => column - 1,
+                    (false, true) => column + 2,
+                    (false, false) => -10,
+                }
+            })
+            .collect();
+        // The register starts at 1, so the first two pixels have to be lit
+        assert_eq!(positions[0], 1);
+
+        positions
+            .iter()
+            .zip(positions.iter().skip(1).chain(positions.last()))
+            .map(|(from, to)| format!("addx {}", to - from))
+            .join("\n")
+    }
+
+    #[test]
+    fn test_part2() {
+        // The example program draws stripes rather than letters
+        let err = part2(TEST_INPUT).unwrap_err().to_string();
+        assert!(err.contains("Unable to read"), "{}", err);
+
+        let screen = [
+            "####.#....####.###..###.#..#............",
+            "#....#....#.....#..#....#..#............",
+            "###..#....###...#..#....####............",
+            "#....#....#.....#...##..#..#............",
+            "#....#....#.....#.....#.#..#............",
+            "####.####.#....###.###..#..#............",
+        ]
+        .join("\n");
+        let program = program_for(&screen);
+        assert_eq!(render_screen(&program).unwrap(), screen);
+        assert_eq!(part2(&program).unwrap(), "ELFISH");
+    }
 }
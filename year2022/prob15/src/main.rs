@@ -1,7 +1,6 @@
+use advent::geometry::diamond::{self, Diamond};
 use advent::prelude::*;
 use parse::parse_input;
-use rayon::prelude::*;
-use std::ops::RangeInclusive;
 
 mod parse;
 
@@ -35,99 +34,51 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn get_intersections_to_row(
-    input: &Vec<(Sensor, Beacon)>,
-    check_row: i32,
-) -> Vec<RangeInclusive<i32>> {
-    // Note that the 'row' in question is for the 'x' coordinate in x,y
-    // So the row is (check_row, y) where y is any value
-    let mut intersections = Vec::new();
-
-    for (Sensor(s_coord), Beacon(b_coord)) in input {
-        // TODO: This can be cached
-        let dist = s_coord.manhattan_distance(b_coord);
-        let dist_to_check_row = (check_row - s_coord.row).abs();
-
-        // Calculate if we intersect the check_row
-        // There are three cases:
-        // 1. No intersection
-        // 2. Intersection in a single point (exactly the dist away)
-        // 3. Intersection in a range of points (less than dist away)
-        match dist - dist_to_check_row {
-            // 2. Intersection in a single point
-            0 => intersections.push(s_coord.column..=s_coord.column),
-            // 3. Intersection in a range of points
-            diff if diff > 0 => {
-                intersections.push((s_coord.column - diff)..=(s_coord.column + diff))
-            }
-            // 1. No intersection
-            _ => {}
-        }
-    }
-
-    // Let's sort the ranges, so that we can combine the overlaps
-    intersections.sort_by(|a, b| a.start().cmp(b.start()));
-
-    intersections
+/// The area around each sensor that's closer than its beacon, where no other beacon can be
+fn get_diamonds(input: &[(Sensor, Beacon)]) -> Vec<Diamond> {
+    input
+        .iter()
+        .map(|(Sensor(s_coord), Beacon(b_coord))| {
+            Diamond::reaching(s_coord.cast().unwrap(), &b_coord.cast().unwrap())
+        })
+        .collect()
 }
 
-fn count_intersections_to_row(input: Vec<(Sensor, Beacon)>, check_row: i32) -> i32 {
-    let intersections = get_intersections_to_row(&input, check_row);
-
-    let mut total_length = 0;
-    let mut current_range = intersections[0].clone();
-    for range in &intersections[1..] {
-        if range.start() <= current_range.end() {
-            if range.end() > current_range.end() {
-                current_range = *current_range.start()..=*range.end();
-            }
-        } else {
-            total_length += current_range.end() - current_range.start();
-            current_range = range.clone();
-        }
-    }
+fn count_intersections_to_row(input: Vec<(Sensor, Beacon)>, check_row: i32) -> i64 {
+    let intersections = diamond::row_coverage(&get_diamonds(&input), check_row as i64);
 
-    total_length += current_range.end() - current_range.start();
+    // A spot that has a beacon in it can't be ruled out from having a beacon
+    let beacons_on_row: HashSet<i32> = input
+        .iter()
+        .filter(|(_, Beacon(b_coord))| b_coord.row == check_row)
+        .map(|(_, Beacon(b_coord))| b_coord.column)
+        .filter(|column| intersections.contains(*column as i64))
+        .collect();
 
-    total_length
+    intersections.total_length() - beacons_on_row.len() as i64
 }
 
-fn search_open_spot(min_coord: i32, max_coord: i32, input: Vec<(Sensor, Beacon)>) -> i64 {
-    (min_coord..=max_coord)
-        .into_par_iter()
-        .find_map_first(|row| {
-            let intersections = get_intersections_to_row(&input, row);
-            let current_end = (*intersections[0].end()).min(max_coord);
-
-            intersections
-                .iter()
-                .scan(current_end, |current_end, range| {
-                    if *range.start() <= *current_end {
-                        if *range.end() > *current_end {
-                            *current_end = (*range.end()).min(max_coord);
-                        }
-                        Some(None)
-                    } else {
-                        // We've found a gap!
-                        let col = *current_end + 1;
-                        Some(Some((col as i64) * 4_000_000 + (row as i64)))
-                    }
-                })
-                .find_map(|x| x)
-        })
-        .unwrap()
+fn search_open_spot(min_coord: i32, max_coord: i32, input: Vec<(Sensor, Beacon)>) -> Result<i64> {
+    let min = GridCoordinate::new(min_coord as i64, min_coord as i64);
+    let max = GridCoordinate::new(max_coord as i64, max_coord as i64);
+
+    // The distress beacon is the only spot in the search area that no sensor can see
+    let spot = diamond::uncovered_points(&get_diamonds(&input), min, max)
+        .into_iter()
+        .next()
+        .ok_or(error!("Every spot in the search area is covered"))?;
+
+    Ok(spot.column * 4_000_000 + spot.row)
 }
 
-fn part1(input: &str) -> Result<i32> {
+fn part1(input: &str) -> Result<i64> {
     let parsed_input = parse_input(input)?;
     Ok(count_intersections_to_row(parsed_input, 2_000_000))
 }
 
 fn part2(input: &str) -> Result<i64> {
     let parsed_input = parse_input(input)?;
-    // NOTE: There's heaps of optimisations to be done here.. but hey, it runs in couple of
-    // seconds in release mode, good enough for me!
-    Ok(search_open_spot(0, 4_000_000, parsed_input))
+    search_open_spot(0, 4_000_000, parsed_input)
 }
 
 #[cfg(test)]
@@ -145,6 +96,6 @@ mod tests {
     #[test]
     fn test_part2() {
         let parsed_input = parse_input(TEST_INPUT).unwrap();
-        assert_eq!(search_open_spot(0, 20, parsed_input), 56_000_011);
+        assert_eq!(search_open_spot(0, 20, parsed_input).unwrap(), 56_000_011);
     }
 }
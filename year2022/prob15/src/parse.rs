@@ -1,44 +1,24 @@
 use crate::{Beacon, Sensor};
-use advent::parsers::nom_signed_digit;
+use advent::parsers::extract_array;
 use advent::prelude::*;
 
-fn nom_coordinate(input: &str) -> IResult<&str, GridCoordinate<i32>> {
-    map(
-        separated_pair(
-            preceded(tag("x="), nom_signed_digit::<i32>),
-            tag(", "),
-            preceded(tag("y="), nom_signed_digit::<i32>),
-        ),
-        |(x, y)| GridCoordinate { column: x, row: y },
-    )
-    .parse(input)
-}
-
-fn nom_sensor(input: &str) -> IResult<&str, Sensor> {
-    map(preceded(tag("Sensor at "), nom_coordinate), Sensor).parse(input)
-}
-
-fn nom_beacon(input: &str) -> IResult<&str, Beacon> {
-    map(
-        preceded(tag(": closest beacon is at "), nom_coordinate),
-        Beacon,
-    )
-    .parse(input)
-}
+fn parse_sensor_beacon_pair(line: &str) -> Result<(Sensor, Beacon)> {
+    let [sx, sy, bx, by] = extract_array(line)?;
 
-fn nom_sensor_beacon_pair(input: &str) -> IResult<&str, (Sensor, Beacon)> {
-    map(pair(nom_sensor, nom_beacon), |(sensor, beacon)| {
-        (sensor, beacon)
-    })
-    .parse(input)
+    Ok((
+        Sensor(GridCoordinate {
+            column: sx,
+            row: sy,
+        }),
+        Beacon(GridCoordinate {
+            column: bx,
+            row: by,
+        }),
+    ))
 }
 
 pub fn parse_input(input: &str) -> Result<Vec<(Sensor, Beacon)>> {
-    let (_, result) = separated_list1(line_ending, nom_sensor_beacon_pair)
-        .parse(input.trim())
-        .map_err(|e| AdventError::ParseError(format!("Failed to parse input: {:?}", e)))?;
-
-    Ok(result)
+    input.trim().lines().map(parse_sensor_beacon_pair).collect()
 }
 
 #[cfg(test)]
@@ -46,43 +26,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_nom_coordinate() {
-        assert_eq!(
-            nom_coordinate("x=2, y=18").unwrap().1,
-            GridCoordinate { row: 18, column: 2 }
-        );
-        assert_eq!(
-            nom_coordinate("x=-2, y=15").unwrap().1,
-            GridCoordinate {
-                row: 15,
-                column: -2
-            }
-        );
-    }
-
-    #[test]
-    fn test_nom_sensor() {
-        assert_eq!(
-            nom_sensor("Sensor at x=2, y=18").unwrap().1,
-            Sensor(GridCoordinate { row: 18, column: 2 })
-        );
-    }
-
-    #[test]
-    fn test_nom_beacon() {
-        assert_eq!(
-            nom_beacon(": closest beacon is at x=-2, y=15").unwrap().1,
-            Beacon(GridCoordinate {
-                row: 15,
-                column: -2
-            })
-        );
-    }
-
-    #[test]
-    fn test_nom_sensor_beacon_pair() {
+    fn test_parse_sensor_beacon_pair() {
         let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
-        let (_, (sensor, beacon)) = nom_sensor_beacon_pair(input).unwrap();
+        let (sensor, beacon) = parse_sensor_beacon_pair(input).unwrap();
         assert_eq!(sensor, Sensor(GridCoordinate { row: 18, column: 2 }));
         assert_eq!(
             beacon,
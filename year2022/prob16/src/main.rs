@@ -1,3 +1,4 @@
+use advent::bitset::BitSet;
 use advent::prelude::*;
 use parse::parse_input;
 
@@ -12,6 +13,9 @@ struct Valve {
     tunnel_ids: Option<Vec<usize>>,
 }
 
+/// The valves that have been opened, by id, with room for up to 128 of them
+type OpenValves = BitSet<2>;
+
 fn main() -> Result<()> {
     let input = get_input(2022, 16)?;
 
@@ -36,30 +40,8 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn get_valve_open_state(valve_id: usize, bitmask: u64) -> bool {
-    (bitmask & (1 << valve_id)) != 0
-}
-
-fn set_valve_open_state(valve_id: usize, bitmask: u64, open: bool) -> u64 {
-    if open {
-        bitmask | (1 << valve_id)
-    } else {
-        bitmask & !(1 << valve_id)
-    }
-}
-
-fn get_open_valves_for_bitmask(valve_map: &HashMap<usize, Valve>, bitmask: u64) -> Vec<&Valve> {
-    valve_map
-        .values()
-        .filter(|v| get_valve_open_state(v.id, bitmask))
-        .collect()
-}
-
-fn get_current_open_valve_flow_rate(valve_map: &HashMap<usize, Valve>, bitmask: u64) -> usize {
-    get_open_valves_for_bitmask(valve_map, bitmask)
-        .iter()
-        .map(|v| v.flow_rate)
-        .sum()
+fn get_current_open_valve_flow_rate(valve_map: &HashMap<usize, Valve>, open: OpenValves) -> usize {
+    open.iter().map(|id| valve_map[&id].flow_rate).sum()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -82,15 +64,15 @@ fn get_current_open_valve_flow_rate(valve_map: &HashMap<usize, Valve>, bitmask:
 ///
 /// Random thoughts:
 ///
-/// * Could I just calculate what the bitmask is for 'all valves with flowrate are open' and exit
+/// * Could I just calculate what the open set is for 'all valves with flowrate are open' and exit
 ///   early based on that?
 fn get_max_pressure(
-    dp: &mut HashMap<(usize, u64, usize), usize>,
+    dp: &mut HashMap<(usize, OpenValves, usize), usize>,
     valve_map: &HashMap<usize, Valve>,
     valves_with_flowrate: &Vec<usize>,
     steps_left: usize,
-    bitmask: u64,
-    all_valves_open_bitmask: u64,
+    open: OpenValves,
+    all_valves_open: OpenValves,
     max_flow_rate: usize,
     current_valve_id: usize,
 ) -> usize {
@@ -100,12 +82,12 @@ fn get_max_pressure(
     }
 
     // Step 2, all valves are open
-    if bitmask == all_valves_open_bitmask {
+    if open == all_valves_open {
         return steps_left * max_flow_rate;
     }
 
     // Step 4, dynamic programming stuff - we'll revisit this
-    if let Some(&pressure) = dp.get(&(steps_left, bitmask, current_valve_id)) {
+    if let Some(&pressure) = dp.get(&(steps_left, open, current_valve_id)) {
         return pressure;
     }
 
@@ -126,10 +108,10 @@ fn get_max_pressure(
     }
 
     let mut valve_pressures = Vec::new();
-    let current_flow_rate = get_current_open_valve_flow_rate(valve_map, bitmask);
+    let current_flow_rate = get_current_open_valve_flow_rate(valve_map, open);
     for valve in valves_with_flowrate {
         // If it's already opened, we just skip it
-        if get_valve_open_state(*valve, bitmask) {
+        if open.contains(*valve) {
             continue;
         }
         // This can be optimised.. but we'll just visit all nodes until we've hit all closed valves
@@ -140,15 +122,14 @@ fn get_max_pressure(
         // We calculate how much pressure will be released during the move (dist) plus 1 for the
         // time it'll take to open the valve after arriving there
         let pressure_during_move = current_flow_rate * (dist + 1);
-        let new_bitmask = set_valve_open_state(*valve, bitmask, true);
         // And now let's calculate the pressure from that state
         let pressure = get_max_pressure(
             dp,
             valve_map,
             valves_with_flowrate,
             steps_left - dist - 1,
-            new_bitmask,
-            all_valves_open_bitmask,
+            open.with(*valve),
+            all_valves_open,
             max_flow_rate,
             *valve,
         );
@@ -160,7 +141,7 @@ fn get_max_pressure(
         // We can only just skip to the end
         let pressure = steps_left * current_flow_rate;
 
-        dp.insert((steps_left, bitmask, current_valve_id), pressure);
+        dp.insert((steps_left, open, current_valve_id), pressure);
 
         return pressure;
     }
@@ -169,7 +150,7 @@ fn get_max_pressure(
     let max_pressure = valve_pressures.into_iter().max().unwrap();
 
     // Step 4, cache the result, so if we happen to find this state again
-    dp.insert((steps_left, bitmask, current_valve_id), max_pressure);
+    dp.insert((steps_left, open, current_valve_id), max_pressure);
 
     max_pressure
 }
@@ -208,10 +189,8 @@ fn part1(input: &str) -> Result<usize> {
         .map(|v| v.id)
         .collect::<Vec<_>>();
 
-    // Calculate the bitmask if all the valves, that have flowrates, would be open
-    let all_valves_open_bitmask = valves_with_flowrate
-        .iter()
-        .fold(0, |acc, &id| set_valve_open_state(id, acc, true));
+    // The state where all the valves, that have flowrates, would be open
+    let all_valves_open: OpenValves = valves_with_flowrate.iter().copied().collect();
 
     let max_flow_rate = valve_map.values().map(|v| v.flow_rate).sum();
 
@@ -220,8 +199,8 @@ fn part1(input: &str) -> Result<usize> {
         &valve_map,
         &valves_with_flowrate,
         steps_left,
-        0,
-        all_valves_open_bitmask,
+        OpenValves::new(),
+        all_valves_open,
         max_flow_rate,
         valve_a_id,
     ))
@@ -1,3 +1,4 @@
+use advent::intervals::{IntervalSet, RangeMap};
 use advent::prelude::*;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -25,6 +26,18 @@ struct Map {
 }
 
 impl Map {
+    /// Build the map as a single piecewise function, so whole ranges can be pushed through it
+    fn to_range_map(&self) -> Result<RangeMap<u64>> {
+        let mut range_map = RangeMap::new();
+        for mapping in &self.mappings {
+            range_map.insert(
+                mapping.source_start..mapping.source_start + mapping.range_length,
+                mapping.destination_start,
+            )?;
+        }
+        Ok(range_map)
+    }
+
     fn convert_number(&self, number: u64) -> u64 {
         for mapping in &self.mappings {
             let mapped_number = mapping.convert_number(number);
@@ -131,6 +144,13 @@ impl Almanac {
         }
         number
     }
+
+    /// Chain every map in the almanac together into one, going straight from seed to location
+    fn to_range_map(&self) -> Result<RangeMap<u64>> {
+        self.maps.iter().try_fold(RangeMap::new(), |combined, map| {
+            Ok(combined.then(&map.to_range_map()?))
+        })
+    }
 }
 
 fn main() -> Result<()> {
@@ -174,19 +194,19 @@ fn part1(input: &str) -> Result<u64> {
 
 fn part2(input: &str) -> Result<u64> {
     let almanac: Almanac = input.parse()?;
-
-    let mut lowest_number = u64::MAX;
-
-    for (seed_start, range_length) in &almanac.seed_ranges {
-        for number in *seed_start..seed_start + range_length {
-            let converted_number = almanac.convert_number(number);
-            if converted_number < lowest_number {
-                lowest_number = converted_number;
-            }
-        }
-    }
-
-    Ok(lowest_number)
+    let range_map = almanac.to_range_map()?;
+
+    let seeds: IntervalSet<u64> = almanac
+        .seed_ranges
+        .iter()
+        .map(|(seed_start, range_length)| *seed_start..seed_start + range_length)
+        .collect();
+
+    range_map
+        .map_set(&seeds)
+        .span()
+        .map(|locations| *locations.start())
+        .ok_or(AdventError::InvalidInput)
 }
 
 #[cfg(test)]
@@ -280,4 +300,14 @@ mod tests {
 
         assert_eq!(almanac.convert_number(79), 82);
     }
+
+    #[test]
+    fn test_to_range_map() {
+        let almanac: Almanac = TEST_INPUT.parse().unwrap();
+        let range_map = almanac.to_range_map().unwrap();
+
+        for seed in 0..120 {
+            assert_eq!(range_map.map(seed), almanac.convert_number(seed));
+        }
+    }
 }
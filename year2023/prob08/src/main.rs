@@ -1,3 +1,4 @@
+use advent::math::number_theory::lcm_all;
 use advent::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -86,30 +87,6 @@ impl FromStr for Map {
     }
 }
 
-fn calculate_lcm(numbers: &[u64]) -> u64 {
-    // Return early if the list is empty
-    if numbers.is_empty() {
-        return 0;
-    }
-
-    // Find the LCM of two numbers
-    fn lcm_of_two(a: u64, b: u64) -> u64 {
-        a * b / gcd(a, b)
-    }
-
-    // Find the greatest common divisor (GCD) of two numbers
-    fn gcd(a: u64, b: u64) -> u64 {
-        if b == 0 {
-            a
-        } else {
-            gcd(b, a % b)
-        }
-    }
-
-    // Use the LCM of two numbers iteratively for the entire list
-    numbers.iter().cloned().fold(1, lcm_of_two)
-}
-
 fn main() -> Result<()> {
     let input = get_input(2023, 8)?;
 
@@ -200,9 +177,7 @@ fn part2(input: &str) -> Result<u64> {
     }
 
     // Calculate the least common multiple of all the distances
-    let lcm = calculate_lcm(&distances);
-
-    Ok(lcm)
+    lcm_all(&distances).ok_or(error!("Least common multiple of the distances overflows"))
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use advent::geometry::polygon::Polygon;
 use advent::prelude::*;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -180,58 +181,6 @@ impl PipeMap {
 
         Ok(directions)
     }
-
-    /// Count how many tiles are closed inside the pipemap loop
-    ///
-    /// We do this by assuming the upper right corner is outisde the loop, then go through each row
-    /// by row and keep track of when we transition in to and out of the loop, counting each tile
-    /// we come across while inside the loop.
-    ///
-    /// NOTE: This assumes that all tiles that are not a part of the loop have been replaced with
-    /// just a Pipe::None
-    fn count_internal_tiles(&self) -> Result<usize> {
-        let mut in_loop = false;
-        let mut count = 0;
-        let mut prev_corner: Option<&Pipe> = None;
-
-        for x in 0..self.height {
-            for y in 0..self.width {
-                let node = GridCoordinate { row: x, column: y }
-                    .get(&self.nodes)
-                    .ok_or(error!("Out of bounds"))?;
-
-                match node {
-                    // Note: I know that the start in my input is a vertical pipe
-                    Pipe::Vertical | Pipe::Start => in_loop = !in_loop,
-                    Pipe::Horizontal => {}
-                    Pipe::CornerNorthEast | Pipe::CornerSouthEast => {
-                        prev_corner = Some(node);
-                    }
-                    Pipe::CornerNorthWest => {
-                        if let Some(prev_corner) = prev_corner {
-                            if prev_corner == &Pipe::CornerSouthEast {
-                                in_loop = !in_loop;
-                            }
-                        }
-                    }
-                    Pipe::CornerSouthWest => {
-                        if let Some(prev_corner) = prev_corner {
-                            if prev_corner == &Pipe::CornerNorthEast {
-                                in_loop = !in_loop;
-                            }
-                        }
-                    }
-                    Pipe::None => {
-                        if in_loop {
-                            count += 1;
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(count)
-    }
 }
 
 fn main() -> Result<()> {
@@ -287,33 +236,27 @@ fn part1(input: &str) -> Result<u32> {
 fn part2(input: &str) -> Result<usize> {
     let map: PipeMap = input.parse()?;
 
-    // Create an empty map to fill with _just_ the loop
-    let mut clean_map = PipeMap {
-        nodes: vec![vec![Pipe::None; map.width]; map.height],
-        height: map.height,
-        width: map.width,
-    };
-
-    // Thread the map until we reach the start again
+    // Thread the map until we reach the start again, collecting every tile along the loop
     let start_coord = map.find_start()?;
-    start_coord.set(&mut clean_map.nodes, Pipe::Start)?;
-
     let start_directions = map.get_start_directions()?;
     let direction = start_directions[0]; // We'll just pick one direction
 
+    let mut loop_tiles = vec![start_coord];
     let mut current_coord = start_coord
         .shifted(direction)
         .ok_or(error!("Invalid start coordinate: {:?}", start_coord))?;
     let mut from_direction = direction.opposite();
 
     while current_coord != start_coord {
-        let current_node = current_coord.get(&map.nodes).unwrap();
-        current_coord.set(&mut clean_map.nodes, current_node.clone())?;
-
+        loop_tiles.push(current_coord);
         (current_coord, from_direction) = map.get_next_node(current_coord, from_direction)?;
     }
 
-    clean_map.count_internal_tiles()
+    // The loop tiles are the boundary of a polygon, so the tiles enclosed by the loop are the
+    // lattice points strictly inside it
+    let polygon = Polygon::from_coordinates(loop_tiles)?;
+
+    Ok(usize::try_from(polygon.interior_points())?)
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use advent::memo::Memo;
 use advent::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,7 +24,6 @@ struct ConditionInfo {
     conditions: Vec<Condition>,
     conditions_len: usize,
     counts: Vec<usize>,
-    cache: HashMap<(usize, usize, usize), usize>,
 }
 
 impl ConditionInfo {
@@ -50,16 +50,18 @@ impl ConditionInfo {
         self.counts = new_counts;
     }
 
+    /// How many ways the unknown springs can be filled in to match the counts
+    fn count_arrangements(&self) -> Result<usize> {
+        self.backtrack(&mut Memo::new(), 0, 0, 0)
+    }
+
     fn backtrack(
-        &mut self,
+        &self,
+        memo: &mut Memo<(usize, usize, usize), usize>,
         pos: usize,
         counts_idx: usize,
         current_damage_count: usize,
     ) -> Result<usize> {
-        if let Some(&cached) = self.cache.get(&(pos, counts_idx, current_damage_count)) {
-            return Ok(cached);
-        }
-
         if pos >= self.conditions_len {
             // The case of ending on a non-damaged spring
             if counts_idx == self.counts.len() && current_damage_count == 0 {
@@ -74,6 +76,19 @@ impl ConditionInfo {
             return Ok(0);
         }
 
+        memo.try_get_or_compute((pos, counts_idx, current_damage_count), |memo| {
+            self.branch(memo, pos, counts_idx, current_damage_count)
+        })
+    }
+
+    /// Try out each condition the spring at `pos` could be in
+    fn branch(
+        &self,
+        memo: &mut Memo<(usize, usize, usize), usize>,
+        pos: usize,
+        counts_idx: usize,
+        current_damage_count: usize,
+    ) -> Result<usize> {
         let (damaged, operational) = match self.conditions[pos] {
             Condition::Damaged => (true, false),
             Condition::Operational => (false, true),
@@ -85,23 +100,20 @@ impl ConditionInfo {
             && counts_idx < self.counts.len()
             && current_damage_count < self.counts[counts_idx]
         {
-            out += self.backtrack(pos + 1, counts_idx, current_damage_count + 1)?;
+            out += self.backtrack(memo, pos + 1, counts_idx, current_damage_count + 1)?;
         }
 
         if operational {
             if current_damage_count == 0 {
-                out += self.backtrack(pos + 1, counts_idx, 0)?;
+                out += self.backtrack(memo, pos + 1, counts_idx, 0)?;
             } else if current_damage_count > 0
                 && counts_idx < self.counts.len()
                 && self.counts[counts_idx] == current_damage_count
             {
-                out += self.backtrack(pos + 1, counts_idx + 1, 0)?;
+                out += self.backtrack(memo, pos + 1, counts_idx + 1, 0)?;
             }
         }
 
-        self.cache
-            .insert((pos, counts_idx, current_damage_count), out);
-
         Ok(out)
     }
 }
@@ -135,7 +147,6 @@ impl FromStr for ConditionInfo {
             conditions,
             conditions_len,
             counts,
-            cache: HashMap::new(),
         })
     }
 }
@@ -165,14 +176,14 @@ fn main() -> Result<()> {
 }
 
 fn part1(input: &str) -> Result<usize> {
-    let mut infos: Vec<ConditionInfo> = input
+    let infos: Vec<ConditionInfo> = input
         .lines()
         .map(|l| l.parse::<ConditionInfo>())
         .collect::<Result<Vec<_>>>()?;
 
     let sum_of_options = infos
-        .iter_mut()
-        .map(|info| info.backtrack(0, 0, 0))
+        .iter()
+        .map(|info| info.count_arrangements())
         .collect::<Result<Vec<_>>>()?
         .iter()
         .sum::<usize>();
@@ -190,8 +201,8 @@ fn part2(input: &str) -> Result<usize> {
     infos.iter_mut().for_each(|info| info.expand(5));
 
     let sum_of_options = infos
-        .iter_mut()
-        .map(|info| info.backtrack(0, 0, 0))
+        .iter()
+        .map(|info| info.count_arrangements())
         .collect::<Result<Vec<_>>>()?
         .iter()
         .sum::<usize>();
@@ -244,24 +255,24 @@ mod tests {
 
     #[test]
     fn test_backtrack_case_1() {
-        let mut info: ConditionInfo = "???.##?. 1,1,3".parse().unwrap();
-        let counter = info.backtrack(0, 0, 0).unwrap();
+        let info: ConditionInfo = "???.##?. 1,1,3".parse().unwrap();
+        let counter = info.count_arrangements().unwrap();
 
         assert_eq!(counter, 1);
     }
 
     #[test]
     fn test_backtrack_case_2() {
-        let mut info: ConditionInfo = ".??..??...?##. 1,1,3".parse().unwrap();
-        let counter = info.backtrack(0, 0, 0).unwrap();
+        let info: ConditionInfo = ".??..??...?##. 1,1,3".parse().unwrap();
+        let counter = info.count_arrangements().unwrap();
 
         assert_eq!(counter, 4);
     }
 
     #[test]
     fn test_backtrack_case_3() {
-        let mut info: ConditionInfo = "?###???????? 3,2,1".parse().unwrap();
-        let counter = info.backtrack(0, 0, 0).unwrap();
+        let info: ConditionInfo = "?###???????? 3,2,1".parse().unwrap();
+        let counter = info.count_arrangements().unwrap();
 
         assert_eq!(counter, 10);
     }
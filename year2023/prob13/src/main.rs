@@ -1,112 +1,48 @@
 use advent::prelude::*;
+use advent::symmetry::{self, Mirror};
 
 #[derive(Debug)]
 struct Pattern {
-    rows: Vec<u32>,
-    cols: Vec<u32>,
+    /// Whether each spot is rock rather than ash
+    rocks: Grid<bool>,
 }
 
 impl FromStr for Pattern {
     type Err = AdventError;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let lines: Vec<_> = s.lines().collect();
-
-        // Since the input is just ash or rocks, we can represent it as bits in a 64 bit binary
-        // number
-        let rows: Vec<u32> = lines
-            .iter()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        '.' => 0,
-                        '#' => 1,
-                        _ => panic!("Unknown node type"),
-                    })
-                    .fold(0, |acc, node| (acc << 1) | node)
-            })
-            .collect();
-
-        let cols: Vec<u32> = (0..lines[0].len())
-            .map(|col| {
-                lines
-                    .iter()
-                    .map(|line| match line.chars().nth(col) {
-                        Some('.') => 0,
-                        Some('#') => 1,
-                        _ => panic!("Unknown node type"),
-                    })
-                    .fold(0, |acc, node| (acc << 1) | node)
-            })
-            .collect();
-        Ok(Pattern { rows, cols })
+        let rocks = Grid::parse(s, |c| match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None,
+        })?;
+
+        Ok(Pattern { rocks })
     }
 }
 
 impl Pattern {
-    fn get_mirror_value(&self, values: &[u32], smudges: u32) -> Option<usize> {
-        // Start by finding any pairs of numbers in the rows, by zipping the rows with it self
-        // offset by 1
-        let pairs: Vec<_> = values
-            .iter()
-            .zip(values.iter().skip(1))
-            .enumerate()
-            .filter_map(|(i, (row1, row2))| {
-                if (row1 ^ row2).count_ones() <= smudges {
-                    Some(i)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if pairs.is_empty() {
-            return None;
-        }
-
-        // Now for each pair, we check if any of them mirror the whole pattern around it
-        'outer: for left_id in pairs.iter() {
-            let mut left = *left_id;
-            let mut right = left + 1;
-
-            let mut smudges = smudges;
-
-            loop {
-                let ones = (values[left] ^ values[right]).count_ones();
-                if smudges > 0 && ones > 0 && ones <= smudges {
-                    smudges -= ones;
-                } else if ones != 0 {
-                    // We found a pair that doesn't match, we can continue onto next pair
-                    continue 'outer;
-                }
-                if left == 0 || right == values.len() - 1 {
-                    if smudges > 0 {
-                        // We are looking at a pattern that is still smudged
-                        continue 'outer;
-                    }
-                    // We reached the edge of the pattern, without breaking the search, meaning the
-                    // pattern is mirrored
-                    return Some(left_id + 1);
-                }
-                left -= 1;
-                right += 1;
-            }
-        }
-
-        None
+    /// Find the mirror line with exactly `smudges` cells that don't match
+    ///
+    /// Returns the number of rows or columns before the line
+    fn get_mirror_value(mirrors: Vec<Mirror>, smudges: usize) -> Option<usize> {
+        mirrors
+            .into_iter()
+            .find(|mirror| mirror.mismatches == smudges)
+            .map(|mirror| mirror.position)
     }
 
     /// Find the horizontal mirror row
     ///
     /// If the pattern is not mirrored horizontally, return None
     /// otherwise return the row index where the mirror is
-    fn get_horizontal_mirror_value(&self, smudges: u32) -> Option<usize> {
-        self.get_mirror_value(&self.rows, smudges)
+    fn get_horizontal_mirror_value(&self, smudges: usize) -> Option<usize> {
+        Self::get_mirror_value(symmetry::horizontal_mirrors(&self.rocks), smudges)
             .map(|row| row * 100)
     }
 
-    fn get_vertical_mirror_value(&self, smudges: u32) -> Option<usize> {
-        self.get_mirror_value(&self.cols, smudges)
+    fn get_vertical_mirror_value(&self, smudges: usize) -> Option<usize> {
+        Self::get_mirror_value(symmetry::vertical_mirrors(&self.rocks), smudges)
     }
 }
 
@@ -190,8 +126,9 @@ mod tests {
     fn test_pattern_from_str() {
         let pattern: Pattern = "#.#\n...\n###".parse().unwrap();
 
-        assert_eq!(pattern.rows, vec![5, 0, 7]);
-        assert_eq!(pattern.cols, vec![5, 1, 5]);
+        assert_eq!(pattern.rocks.row(0), Some(&[true, false, true][..]));
+        assert_eq!(pattern.rocks.row(1), Some(&[false, false, false][..]));
+        assert_eq!(pattern.rocks.row(2), Some(&[true, true, true][..]));
     }
 
     #[test]
@@ -232,13 +169,7 @@ mod tests {
     #[test]
     fn test_get_smudged_mirror_row() {
         let test_patterns: Vec<Pattern> = parse_input(TEST_INPUT).unwrap();
-        assert_eq!(
-            test_patterns[0].get_mirror_value(&test_patterns[0].rows, 1),
-            Some(3)
-        );
-        assert_eq!(
-            test_patterns[1].get_mirror_value(&test_patterns[1].rows, 1),
-            Some(1)
-        );
+        assert_eq!(test_patterns[0].get_horizontal_mirror_value(1), Some(300));
+        assert_eq!(test_patterns[1].get_horizontal_mirror_value(1), Some(100));
     }
 }
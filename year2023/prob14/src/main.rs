@@ -1,9 +1,9 @@
+use advent::cycle;
 use advent::prelude::*;
-use std::str::FromStr;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone)]
 struct Platform {
-    nodes: Vec<Vec<Node>>,
+    nodes: Grid<Node>,
 }
 
 impl Platform {
@@ -11,115 +11,38 @@ impl Platform {
     ///
     /// Tilting a platform will cause all RoundRock to slide until they reach the edge or hit
     /// another rock
-    fn tilt_platform(&mut self, direction: &Direction) -> Result<()> {
-        let row_count = self.nodes.len();
-        let col_count = self.nodes[0].len();
-
-        match direction {
-            Direction::North => {
-                for row_idx in 1..row_count {
-                    for col_idx in 0..col_count {
-                        let node = &self.nodes[row_idx][col_idx];
-                        if let Node::RoundRock = node {
-                            // Let's see how far up we can get
-                            for i in (1..=row_idx).rev() {
-                                let next_node = &self.nodes[i - 1][col_idx];
-                                if let Node::Space = next_node {
-                                    // Swap the nodes
-                                    // TODO: No need to swap at every step
-                                    self.nodes[i - 1][col_idx] = Node::RoundRock;
-                                    self.nodes[i][col_idx] = Node::Space;
-                                } else {
-                                    // We can't go further up
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Direction::South => {
-                for row_idx in (0..row_count - 1).rev() {
-                    for col_idx in 0..col_count {
-                        let node = &self.nodes[row_idx][col_idx];
-                        if let Node::RoundRock = node {
-                            for i in row_idx + 1..row_count {
-                                let next_node = &self.nodes[i][col_idx];
-                                if let Node::Space = next_node {
-                                    // Swap the nodes
-                                    self.nodes[i][col_idx] = Node::RoundRock;
-                                    self.nodes[i - 1][col_idx] = Node::Space;
-                                } else {
-                                    // We can't go further down
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Direction::West => {
-                for row_idx in 0..row_count {
-                    for col_idx in 1..col_count {
-                        let node = &self.nodes[row_idx][col_idx];
-                        if let Node::RoundRock = node {
-                            for i in (1..=col_idx).rev() {
-                                let next_node = &self.nodes[row_idx][i - 1];
-                                if let Node::Space = next_node {
-                                    // Swap the nodes
-                                    self.nodes[row_idx][i - 1] = Node::RoundRock;
-                                    self.nodes[row_idx][i] = Node::Space;
-                                } else {
-                                    // We can't go further left
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Direction::East => {
-                for row_idx in 0..row_count {
-                    for col_idx in (0..col_count - 1).rev() {
-                        let node = &self.nodes[row_idx][col_idx];
-                        if let Node::RoundRock = node {
-                            for i in col_idx + 1..col_count {
-                                let next_node = &self.nodes[row_idx][i];
-                                if let Node::Space = next_node {
-                                    // Swap the nodes
-                                    self.nodes[row_idx][i] = Node::RoundRock;
-                                    self.nodes[row_idx][i - 1] = Node::Space;
-                                } else {
-                                    // We can't go further right
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    fn tilt_platform(&mut self, direction: CompassDirection) {
+        self.nodes.slide(
+            direction,
+            |node| *node == Node::RoundRock,
+            |node| *node == Node::Space,
+        );
+    }
 
-        Ok(())
+    /// Run a full spin cycle, tilting north, west, south and then east
+    fn spin_cycle(&self) -> Self {
+        let mut platform = self.clone();
+        for direction in [
+            CompassDirection::North,
+            CompassDirection::West,
+            CompassDirection::South,
+            CompassDirection::East,
+        ] {
+            platform.tilt_platform(direction);
+        }
+        platform
     }
 
     /// Calculate platform load
     ///
     /// The load caused by a single RoundRock is equal to the number of rows from the south edge
     fn get_load(&self) -> usize {
-        let row_count = self.nodes.len();
-
-        let mut load = 0;
+        let row_count = self.nodes.height();
 
-        for (idx, row) in self.nodes.iter().enumerate() {
-            for node in row {
-                if let Node::RoundRock = node {
-                    load += row_count - idx;
-                }
-            }
-        }
-
-        load
+        self.nodes
+            .positions(|node| *node == Node::RoundRock)
+            .map(|coord| row_count - coord.row)
+            .sum()
     }
 }
 
@@ -127,28 +50,10 @@ impl FromStr for Platform {
     type Err = AdventError;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let mut nodes = Vec::new();
-
-        for line in s.lines() {
-            let mut row = Vec::new();
-            for c in line.chars() {
-                row.push(Node::from(c));
-            }
-            nodes.push(row);
-        }
-
-        Ok(Platform { nodes })
+        Ok(Platform { nodes: s.parse()? })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    West,
-    East,
-}
-
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 enum Node {
     RoundRock, // O
@@ -156,13 +61,15 @@ enum Node {
     Space,     // .
 }
 
-impl From<char> for Node {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Node {
+    type Error = AdventError;
+
+    fn try_from(c: char) -> Result<Self> {
         match c {
-            'O' => Node::RoundRock,
-            '#' => Node::CubeRock,
-            '.' => Node::Space,
-            _ => panic!("Invalid node type"),
+            'O' => Ok(Node::RoundRock),
+            '#' => Ok(Node::CubeRock),
+            '.' => Ok(Node::Space),
+            _ => Err(error!("Invalid node type {:?}", c)),
         }
     }
 }
@@ -194,46 +101,16 @@ fn main() -> Result<()> {
 fn part1(input: &str) -> Result<usize> {
     let mut platform: Platform = input.parse()?;
 
-    platform.tilt_platform(&Direction::North)?;
+    platform.tilt_platform(CompassDirection::North);
 
     Ok(platform.get_load())
 }
 
 fn part2(input: &str) -> Result<usize> {
-    let mut platform: Platform = input.parse()?;
+    let platform: Platform = input.parse()?;
 
-    // Keep track of how many times we've seen a state, and which direction we tilted to get here
-    let mut iterations = 0;
-    let goal = 1_000_000_000;
-    let directions = [
-        Direction::North,
-        Direction::West,
-        Direction::South,
-        Direction::East,
-    ];
-    let mut seen: HashMap<Platform, usize> = HashMap::new();
-
-    // let mut seen: HashSet<Platform> = HashSet::new();
-    let mut loop_found = false;
-
-    // Start rotating and store states along with the previous direction
-    while iterations < goal {
-        for direction in directions {
-            platform.tilt_platform(&direction)?;
-        }
-
-        if !loop_found {
-            if let Some(loop_start) = seen.get(&platform) {
-                let loop_len = iterations - loop_start;
-                let loops = (goal - iterations) / loop_len;
-                iterations += (loops - 2) * loop_len;
-                loop_found = true;
-            }
-            seen.insert(platform.clone(), iterations);
-        }
-
-        iterations += 1;
-    }
+    // The platform settles into a loop long before we get anywhere near a billion spins
+    let platform = cycle::state_after(platform, Platform::spin_cycle, 1_000_000_000);
 
     Ok(platform.get_load())
 }
@@ -258,113 +135,119 @@ mod tests {
     fn test_platform_from_str() {
         let platform: Platform = "O..#\n....\n#OO#".parse().unwrap();
 
-        assert_eq!(platform.nodes.len(), 3);
+        assert_eq!(platform.nodes.height(), 3);
         assert_eq!(
-            platform.nodes[0],
-            vec![Node::RoundRock, Node::Space, Node::Space, Node::CubeRock]
+            platform.nodes.row(0),
+            Some(&[Node::RoundRock, Node::Space, Node::Space, Node::CubeRock][..])
         );
         assert_eq!(
-            platform.nodes[1],
-            vec![Node::Space, Node::Space, Node::Space, Node::Space]
+            platform.nodes.row(1),
+            Some(&[Node::Space, Node::Space, Node::Space, Node::Space][..])
         );
         assert_eq!(
-            platform.nodes[2],
-            vec![
-                Node::CubeRock,
-                Node::RoundRock,
-                Node::RoundRock,
-                Node::CubeRock
-            ]
+            platform.nodes.row(2),
+            Some(
+                &[
+                    Node::CubeRock,
+                    Node::RoundRock,
+                    Node::RoundRock,
+                    Node::CubeRock
+                ][..]
+            )
         );
     }
 
     #[test]
     fn test_platform_tilt_north() {
         let mut platform: Platform = "O..#\n....\n#OO#".parse().unwrap();
-        platform.tilt_platform(&Direction::North).unwrap();
+        platform.tilt_platform(CompassDirection::North);
 
-        assert_eq!(platform.nodes.len(), 3);
+        assert_eq!(platform.nodes.height(), 3);
         assert_eq!(
-            platform.nodes[0],
-            vec![
-                Node::RoundRock,
-                Node::RoundRock,
-                Node::RoundRock,
-                Node::CubeRock
-            ]
+            platform.nodes.row(0),
+            Some(
+                &[
+                    Node::RoundRock,
+                    Node::RoundRock,
+                    Node::RoundRock,
+                    Node::CubeRock
+                ][..]
+            )
         );
         assert_eq!(
-            platform.nodes[1],
-            vec![Node::Space, Node::Space, Node::Space, Node::Space]
+            platform.nodes.row(1),
+            Some(&[Node::Space, Node::Space, Node::Space, Node::Space][..])
         );
         assert_eq!(
-            platform.nodes[2],
-            vec![Node::CubeRock, Node::Space, Node::Space, Node::CubeRock]
+            platform.nodes.row(2),
+            Some(&[Node::CubeRock, Node::Space, Node::Space, Node::CubeRock][..])
         );
     }
 
     #[test]
     fn test_platform_tilt_south() {
         let mut platform: Platform = "O..#\n....\n#OO#".parse().unwrap();
-        platform.tilt_platform(&Direction::South).unwrap();
+        platform.tilt_platform(CompassDirection::South);
 
-        assert_eq!(platform.nodes.len(), 3);
+        assert_eq!(platform.nodes.height(), 3);
         assert_eq!(
-            platform.nodes[0],
-            vec![Node::Space, Node::Space, Node::Space, Node::CubeRock]
+            platform.nodes.row(0),
+            Some(&[Node::Space, Node::Space, Node::Space, Node::CubeRock][..])
         );
         assert_eq!(
-            platform.nodes[1],
-            vec![Node::RoundRock, Node::Space, Node::Space, Node::Space]
+            platform.nodes.row(1),
+            Some(&[Node::RoundRock, Node::Space, Node::Space, Node::Space][..])
         );
         assert_eq!(
-            platform.nodes[2],
-            vec![
-                Node::CubeRock,
-                Node::RoundRock,
-                Node::RoundRock,
-                Node::CubeRock
-            ]
+            platform.nodes.row(2),
+            Some(
+                &[
+                    Node::CubeRock,
+                    Node::RoundRock,
+                    Node::RoundRock,
+                    Node::CubeRock
+                ][..]
+            )
         );
     }
 
     #[test]
     fn test_platform_tilt_west() {
         let mut platform: Platform = ".O.#\n....\n#.O#".parse().unwrap();
-        platform.tilt_platform(&Direction::West).unwrap();
+        platform.tilt_platform(CompassDirection::West);
 
-        assert_eq!(platform.nodes.len(), 3);
+        assert_eq!(platform.nodes.height(), 3);
         assert_eq!(
-            platform.nodes[0],
-            vec![Node::RoundRock, Node::Space, Node::Space, Node::CubeRock]
+            platform.nodes.row(0),
+            Some(&[Node::RoundRock, Node::Space, Node::Space, Node::CubeRock][..])
         );
         assert_eq!(
-            platform.nodes[1],
-            vec![Node::Space, Node::Space, Node::Space, Node::Space]
+            platform.nodes.row(1),
+            Some(&[Node::Space, Node::Space, Node::Space, Node::Space][..])
         );
         assert_eq!(
-            platform.nodes[2],
-            vec![Node::CubeRock, Node::RoundRock, Node::Space, Node::CubeRock]
+            platform.nodes.row(2),
+            Some(&[Node::CubeRock, Node::RoundRock, Node::Space, Node::CubeRock][..])
         );
     }
 
     #[test]
     fn test_platform_tilt_east() {
         let mut platform: Platform = "O..#\n....\n#O.#".parse().unwrap();
-        platform.tilt_platform(&Direction::East).unwrap();
+        platform.tilt_platform(CompassDirection::East);
 
-        assert_eq!(platform.nodes.len(), 3);
+        assert_eq!(platform.nodes.height(), 3);
         assert_eq!(
-            platform.nodes[0],
-            vec![Node::Space, Node::Space, Node::RoundRock, Node::CubeRock]
+            platform.nodes.row(0),
+            Some(&[Node::Space, Node::Space, Node::RoundRock, Node::CubeRock][..])
         );
         assert_eq!(
-            platform.nodes[1],
-            vec![Node::Space, Node::Space, Node::Space, Node::Space]
+            platform.nodes.row(1),
+            Some(&[Node::Space, Node::Space, Node::Space, Node::Space][..])
         );
         assert_eq!(
-            platform.nodes[2],
-            vec![Node::CubeRock, Node::Space, Node::RoundRock, Node::CubeRock]
+            platform.nodes.row(2),
+            Some(&[Node::CubeRock, Node::Space, Node::RoundRock, Node::CubeRock][..])
         );
     }
 
@@ -374,7 +257,7 @@ mod tests {
 
         assert_eq!(platform.get_load(), 5);
 
-        platform.tilt_platform(&Direction::North).unwrap();
+        platform.tilt_platform(CompassDirection::North);
 
         assert_eq!(platform.get_load(), 9);
     }
@@ -1,28 +1,20 @@
 use advent::prelude::*;
+use advent::search::dijkstra;
 
 struct Layout {
     nodes: Vec<Vec<u8>>,
 }
 
-type HeatLoss = i32;
 type StraightDistance = i32;
-type Key = (
-    HeatLoss,
-    GridCoordinate<usize>,
-    CompassDirection,
-    StraightDistance,
-);
+type State = (GridCoordinate<usize>, CompassDirection, StraightDistance);
 
 /// Notes
 ///
 /// * Since we have a limit of straight lines, we can't just do a basic flood algorithm (is that
 ///   what it's called?)
 /// * We can also easily have loops, since we can turn any way (except backwards)
-/// * Might be worth having as core for each path we are looking at, which is not just the current
-///   cost but also have a penalty for just how far we are from the goal? This way we don't waste
-///   time on paths that are really far away
-///     - When we find a path this way, we will have to continue processing all other paths that
-///       are still in the queue, but we can exit as soon as they go over the best known cost
+/// * The state we search over is not just the coordinate, but also the direction we're heading
+///   and how far we've gone straight, since those limit where we can go next
 impl Layout {
     /// Find the cheapest path from one coordinate to another
     ///
@@ -37,101 +29,61 @@ impl Layout {
         min_straight_line: i32,
         max_straight_line: i32,
     ) -> Result<i32> {
-        let mut seen: HashSet<(GridCoordinate<usize>, CompassDirection, StraightDistance)> =
-            HashSet::new();
-
-        // This is a max heap, so we store the scores negative to turn it into a min heap
-        let mut heap: BinaryHeap<Key> = BinaryHeap::new();
-
-        // The heap is a tuple of:
-        //
-        //   * The total heat loss so far at this node (stored negative to turn max-heap into min-heap)
-        //   * The coordinate of the current node
-        //   * The direction we are heading
-        //   * How many nodes we have gone straight in a row - if we just turned, it's 0, this
-        //     includes in the start
-        //
-        // We start by setting the heap to the two first steps having been taken, basically, since
-        // we start in the upper right corner at (0, 0) we can either so South of East. Since we
-        // don't count the heat from the start node, the initial heat will be the first node we
-        // step on
-        heap.push((
-            -self.node_heat(GridCoordinate { row: 1, column: 0 }),
-            GridCoordinate { row: 1, column: 0 },
-            CompassDirection::South,
-            0,
-        ));
-        heap.push((
-            -self.node_heat(GridCoordinate { row: 0, column: 1 }),
-            GridCoordinate { row: 0, column: 1 },
-            CompassDirection::East,
-            0,
-        ));
-
-        while !heap.is_empty() {
-            let (heat_loss, coord, direction, straight_distance) =
-                heap.pop().ok_or(error!("No more nodes in heap"))?;
-
-            // Work with heat loss as positive
-            let heat_loss = -heat_loss;
-
-            if coord == to {
-                return Ok(heat_loss);
-            }
-
-            if !seen.insert((coord, direction, straight_distance)) {
-                // We had already seen this node, so we can skip it
-                continue;
-            }
-
-            // Continue the path in all directions, except for:
-            //  * Backwards
-            //  * Forwards if we exceed the max distance
-            //  * If we go off the grid
-            for next_direction in &[
-                CompassDirection::North,
-                CompassDirection::South,
-                CompassDirection::East,
-                CompassDirection::West,
-            ] {
-                // Can't go backwards
-                if next_direction == &direction.opposite() {
-                    continue;
-                }
-
-                // If forwards, we can only continue if we don't exceed max distance
-                if next_direction == &direction {
-                    if straight_distance < (max_straight_line - 1) {
-                        if let Some(next_coord) = coord.shifted(direction) {
-                            if next_coord.within_grid(&self.nodes) {
-                                let next_node_heat = self.node_heat(next_coord);
-                                heap.push((
-                                    -(heat_loss + next_node_heat),
-                                    next_coord,
-                                    direction,
-                                    straight_distance + 1,
-                                ));
-                            }
+        // The straight distance is how many nodes we have gone straight in a row, not counting
+        // the first step after a turn. We start in the upper left corner at (0, 0) and can head
+        // either South or East, and we set the straight distance to -1 so that the first step
+        // from the start is counted as the step right after a turn
+        let origin = GridCoordinate { row: 0, column: 0 };
+        let starts = [
+            (origin, CompassDirection::South, -1),
+            (origin, CompassDirection::East, -1),
+        ];
+
+        let result = dijkstra(
+            starts,
+            |&(coord, direction, straight_distance): &State| {
+                // Continue the path in all directions, except for:
+                //  * Backwards
+                //  * Forwards if we exceed the max distance
+                //  * Turning before we've reached the min distance
+                //  * If we go off the grid
+                [
+                    CompassDirection::North,
+                    CompassDirection::South,
+                    CompassDirection::East,
+                    CompassDirection::West,
+                ]
+                .into_iter()
+                .filter_map(move |next_direction| {
+                    let next_straight_distance = if next_direction == direction {
+                        if straight_distance >= max_straight_line - 1 {
+                            return None;
                         }
-                    }
-                    continue;
-                }
-                if straight_distance >= min_straight_line {
-                    // Try to turn
-                    if let Some(next_coord) = coord.shifted(*next_direction) {
-                        if next_coord.within_grid(&self.nodes) {
-                            heap.push((
-                                -(heat_loss + self.node_heat(next_coord)),
-                                next_coord,
-                                *next_direction,
-                                0,
-                            ));
+                        straight_distance + 1
+                    } else {
+                        if next_direction == direction.opposite()
+                            || straight_distance < min_straight_line
+                        {
+                            return None;
                         }
+                        0
+                    };
+
+                    let next_coord = coord.shifted(next_direction)?;
+                    if !next_coord.within_grid(&self.nodes) {
+                        return None;
                     }
-                }
-            }
-        }
-        Err(error!("No path found"))
+
+                    Some((
+                        (next_coord, next_direction, next_straight_distance),
+                        self.node_heat(next_coord),
+                    ))
+                })
+            },
+            |(coord, _, _)| *coord == to,
+        );
+
+        result.cost().ok_or(error!("No path found"))
     }
 
     fn node_heat(&self, coord: GridCoordinate<usize>) -> i32 {
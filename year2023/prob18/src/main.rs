@@ -1,3 +1,4 @@
+use advent::geometry::polygon::Polygon;
 use advent::prelude::*;
 
 /// An instruction is in the form of:
@@ -64,66 +65,16 @@ impl Instruction {
     }
 }
 
-fn get_nodes(instructions: &[Instruction]) -> Vec<GridCoordinate<i64>> {
-    let mut current: GridCoordinate<i64> = GridCoordinate { row: 0, column: 0 };
-    let mut nodes = vec![];
-
-    for instruction in instructions {
-        let dist = instruction.distance;
-
-        current = match instruction.direction {
-            GridDirection::Up => GridCoordinate {
-                row: current.row - dist,
-                column: current.column,
-            },
-            GridDirection::Down => GridCoordinate {
-                row: current.row + dist,
-                column: current.column,
-            },
-            GridDirection::Left => GridCoordinate {
-                row: current.row,
-                column: current.column - dist,
-            },
-            GridDirection::Right => GridCoordinate {
-                row: current.row,
-                column: current.column + dist,
-            },
-            _ => panic!("Bad direction"),
-        };
-        nodes.push(current);
-    }
-    nodes
-}
-
-/// Get the area of a polygon
-///
-/// Using the Shoelace algorithm
-fn get_polygon_area(nodes: &[GridCoordinate<i64>]) -> i64 {
-    let num_of_nodes = nodes.len();
-    let mut sum1: i64 = 0;
-    let mut sum2: i64 = 0;
-    let mut border: i64 = 0;
-
-    for i in 0..(num_of_nodes - 1) {
-        sum1 += nodes[i].row * nodes[i + 1].column;
-        sum2 += nodes[i].column * nodes[i + 1].row;
-        border +=
-            (nodes[i].row - nodes[i + 1].row).abs() + (nodes[i].column - nodes[i + 1].column).abs();
-    }
-
-    sum1 += nodes[num_of_nodes - 1].row * nodes[0].column;
-    sum2 += nodes[num_of_nodes - 1].column * nodes[0].row;
-    border += (nodes[num_of_nodes - 1].row - nodes[0].row).abs()
-        + (nodes[num_of_nodes - 1].column - nodes[0].column).abs();
-
-    let inside = (sum1 - sum2).abs() / 2;
+/// Get the area of the lagoon, which is the trench itself along with everything inside it
+fn get_lagoon_area(instructions: &[Instruction]) -> i64 {
+    let polygon = Polygon::from_steps(
+        GridCoordinate { row: 0, column: 0 },
+        instructions
+            .iter()
+            .map(|instruction| (instruction.direction, instruction.distance)),
+    );
 
-    // Note: Full disclosure, I have no idea why this works, I just noticed that the number I was
-    // getting from shoelace algorithm was less then expected in example.. and counting the border
-    // was going way over, then half of the border (half outside and half inside the shoelace?)
-    // was one off ... soooo +1 and that matched the test case plus part 1 (which I solved first
-    // with flood fill), so here we are.
-    inside + (border / 2) + 1
+    polygon.lattice_points()
 }
 
 fn main() -> Result<()> {
@@ -155,9 +106,8 @@ fn part1(input: &str) -> Result<i64> {
         .lines()
         .map(Instruction::from_basic)
         .collect::<Result<Vec<Instruction>>>()?;
-    let nodes = get_nodes(&instructions);
 
-    Ok(get_polygon_area(&nodes))
+    Ok(get_lagoon_area(&instructions))
 }
 
 fn part2(input: &str) -> Result<i64> {
@@ -165,9 +115,8 @@ fn part2(input: &str) -> Result<i64> {
         .lines()
         .map(Instruction::from_hex)
         .collect::<Result<Vec<Instruction>>>()?;
-    let nodes = get_nodes(&instructions);
 
-    Ok(get_polygon_area(&nodes))
+    Ok(get_lagoon_area(&instructions))
 }
 
 #[cfg(test)]
@@ -203,7 +152,7 @@ mod tests {
     }
 
     #[test]
-    fn test_get_nodes() {
+    fn test_get_lagoon_area() {
         let instructions: Vec<Instruction> = vec![
             Instruction::from_basic("R 8 (#70c710)").unwrap(),
             Instruction::from_basic("D 4 (#70c710)").unwrap(),
@@ -211,12 +160,7 @@ mod tests {
             Instruction::from_basic("U 4 (#70c710)").unwrap(),
         ];
 
-        let nodes = get_nodes(&instructions);
-
-        assert_eq!(nodes.len(), 4);
-        assert_eq!(nodes[0], GridCoordinate { row: 0, column: 8 });
-        assert_eq!(nodes[1], GridCoordinate { row: 4, column: 8 });
-        assert_eq!(nodes[2], GridCoordinate { row: 4, column: 0 });
-        assert_eq!(nodes[3], GridCoordinate { row: 0, column: 0 });
+        // A 9x5 rectangle, with the trench included
+        assert_eq!(get_lagoon_area(&instructions), 45);
     }
 }
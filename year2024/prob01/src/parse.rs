@@ -1,4 +1,4 @@
-use advent::parsers::nom_signed_digit;
+use advent::parsers::{nom_signed_digit, parse_all};
 use advent::prelude::*;
 
 fn nom_line(input: &str) -> IResult<&str, (i32, i32)> {
@@ -11,9 +11,7 @@ fn nom_line(input: &str) -> IResult<&str, (i32, i32)> {
 }
 
 pub fn parse_input(input: &str) -> Result<Vec<(i32, i32)>> {
-    let (_, pairs) = separated_list1(newline, nom_line).parse(input)?;
-
-    Ok(pairs)
+    parse_all(separated_list1(newline, nom_line), input)
 }
 
 #[cfg(test)]
@@ -30,5 +28,7 @@ mod tests {
     fn test_parse_input() {
         let input = "1 2\n3 4";
         assert_eq!(parse_input(input).unwrap(), vec![(1, 2), (3, 4)]);
+        assert_eq!(parse_input("1 2\n3 4\n").unwrap(), vec![(1, 2), (3, 4)]);
+        assert!(parse_input("1 2\n3,4\n5 6").is_err());
     }
 }
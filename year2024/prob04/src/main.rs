@@ -53,8 +53,8 @@ fn search_xmas(grid: &[&[char]], y: usize, x: usize, direction: GridDirection) -
     };
 
     for (i, c) in XMAS.iter().enumerate() {
-        let y = ((y as i32) + (i as i32) * vector.1) as usize;
-        let x = ((x as i32) + (i as i32) * vector.0) as usize;
+        let y = ((y as i32) + (i as i32) * vector.row) as usize;
+        let x = ((x as i32) + (i as i32) * vector.column) as usize;
         if grid[y][x] != *c {
             return false;
         }
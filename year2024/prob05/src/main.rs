@@ -1,6 +1,6 @@
+use advent::graph::Graph;
 use advent::prelude::*;
 use parse::{parse_input, PageOrder};
-use std::cmp::Ordering;
 
 mod parse;
 
@@ -28,90 +28,48 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-/// Create a map of pages that must be before a given page
+/// Put the pages in the order the rules ask for
 ///
-/// The key is a page and the value is a list of values that must come after it, this can be used
-/// to see if we have seen any of the 'after' pages before the key page, which means the order is invalid
-fn page_before_map(page_orders: Vec<PageOrder>) -> HashMap<usize, HashSet<usize>> {
-    let mut map: HashMap<usize, HashSet<usize>> = HashMap::new();
+/// The rules as a whole go round in circles, but the ones between the pages in a single update
+/// never do, so only those get turned into a graph to sort.
+fn sort_pages(page_orders: &[PageOrder], pages: &[usize]) -> Result<Vec<usize>> {
+    let mut graph = Graph::directed();
+    for page in pages {
+        graph.add_node(*page);
+    }
     for (before, after) in page_orders {
-        if let Some(set) = map.get_mut(&before) {
-            set.insert(after);
-        } else {
-            map.insert(before, HashSet::from_iter(vec![after]));
+        if graph.contains(before) && graph.contains(after) {
+            graph.add_edge(*before, *after);
         }
     }
-    map
+
+    Ok(graph.topological_sort()?.into_iter().copied().collect())
 }
 
 fn part1(input: &str) -> Result<usize> {
     let (page_orders, page_lists) = parse_input(input)?;
-    let before_map = page_before_map(page_orders);
 
     let mut return_val = 0;
-    'pages: for page_list in page_lists {
-        let mut seen = HashSet::new();
-        for digit in &page_list {
-            // Check if any of the 'seen' digits are in the 'after' values
-            if let Some(after) = before_map.get(digit) {
-                if after.intersection(&seen).count() > 0 {
-                    continue 'pages;
-                }
-            }
-            seen.insert(*digit);
+    for page_list in page_lists {
+        if sort_pages(&page_orders, &page_list)? == page_list {
+            // Already in the right order, so we want the middle value
+            return_val += page_list[page_list.len() / 2];
         }
-
-        // We reached the end without any issues
-        // Now get the middle value of the page_list
-        return_val += page_list[page_list.len() / 2];
     }
     Ok(return_val)
 }
 
 fn part2(input: &str) -> Result<usize> {
     let (page_orders, page_lists) = parse_input(input)?;
-    let before_map = page_before_map(page_orders);
-
-    let mut invalid_page_lists = Vec::new();
-    'pages: for page_list in page_lists {
-        let mut seen = HashSet::new();
-        for digit in &page_list {
-            // Check if any of the 'seen' digits are in the 'after' values
-            if let Some(after) = before_map.get(digit) {
-                if after.intersection(&seen).count() > 0 {
-                    invalid_page_lists.push(page_list);
-                    continue 'pages;
-                }
-            }
-            seen.insert(*digit);
+
+    let mut sum = 0;
+    for page_list in page_lists {
+        let sorted = sort_pages(&page_orders, &page_list)?;
+        if sorted != page_list {
+            // Only the ones that were out of order count, and we want the middle value
+            sum += sorted[sorted.len() / 2];
         }
     }
-
-    // Go through the invalid pages and sort them based on the before_map
-    let sum = invalid_page_lists
-        .iter()
-        .map(|page_list| {
-            let mut sorted = page_list.clone();
-            sorted.sort_by(|a, b| {
-                // Check if a needs to be before b
-                if let Some(after) = before_map.get(a) {
-                    if after.contains(b) {
-                        return Ordering::Less;
-                    }
-                }
-                // Check the other way
-                if let Some(after) = before_map.get(b) {
-                    if after.contains(a) {
-                        return Ordering::Greater;
-                    }
-                }
-                // Otherwise they're the same and it doesn't matter
-                Ordering::Equal
-            });
-            // Then we return the middle value
-            sorted[sorted.len() / 2]
-        })
-        .sum();
     Ok(sum)
 }
 
@@ -132,10 +90,11 @@ mod tests {
     }
 
     #[test]
-    fn test_page_before_map() {
-        let page_orders = vec![(1, 4), (3, 5), (3, 4)];
-        let map = page_before_map(page_orders);
-        assert_eq!(map.get(&1).unwrap(), &HashSet::from_iter(vec![4]));
-        assert_eq!(map.get(&3).unwrap(), &HashSet::from_iter(vec![4, 5]));
+    fn test_sort_pages() {
+        let page_orders = vec![(1, 4), (3, 5), (3, 4), (4, 5)];
+
+        assert_eq!(sort_pages(&page_orders, &[4, 3, 1]).unwrap(), vec![3, 1, 4]);
+        assert_eq!(sort_pages(&page_orders, &[5, 4]).unwrap(), vec![4, 5]);
+        assert!(sort_pages(&[(1, 2), (2, 1)], &[1, 2]).is_err());
     }
 }
@@ -1,3 +1,4 @@
+use advent::memo::Memo;
 use advent::prelude::*;
 use parse::parse_input;
 
@@ -47,7 +48,7 @@ fn stone_tick(stone: usize) -> Result<(usize, Option<usize>)> {
 fn get_stone_count_after_ticks(
     stone: usize,
     ticks: usize,
-    cache: &mut HashMap<(usize, usize), usize>,
+    memo: &mut Memo<(usize, usize), usize>,
 ) -> usize {
     if ticks == 0 {
         // TODO: Is this base case correct?
@@ -55,38 +56,25 @@ fn get_stone_count_after_ticks(
         return 1;
     }
 
-    let key = (stone, ticks);
-    if let Some(cached) = cache.get(&key) {
-        // If we've already cached the count, we can just return it
-        return *cached;
-    }
-
-    match stone_tick(stone) {
-        Ok((new_stone, None)) => {
-            let count = get_stone_count_after_ticks(new_stone, ticks - 1, cache);
-            cache.insert(key, count);
-            count
-        }
+    memo.get_or_compute((stone, ticks), |memo| match stone_tick(stone) {
+        Ok((new_stone, None)) => get_stone_count_after_ticks(new_stone, ticks - 1, memo),
         Ok((left_stone, Some(right_stone))) => {
-            let count = get_stone_count_after_ticks(left_stone, ticks - 1, cache)
-                + get_stone_count_after_ticks(right_stone, ticks - 1, cache);
-
-            cache.insert(key, count);
-            count
+            get_stone_count_after_ticks(left_stone, ticks - 1, memo)
+                + get_stone_count_after_ticks(right_stone, ticks - 1, memo)
         }
         _ => panic!("Invalid stone"),
-    }
+    })
 }
 
 fn part1(input: &str) -> Result<usize> {
     let stones = parse_input(input)?;
     let steps = 25;
 
-    let mut cache: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut memo = Memo::new();
     let mut count = 0;
 
     for stone in stones {
-        let stone_count = get_stone_count_after_ticks(stone, steps, &mut cache);
+        let stone_count = get_stone_count_after_ticks(stone, steps, &mut memo);
         count += stone_count;
     }
 
@@ -97,11 +85,11 @@ fn part2(input: &str) -> Result<usize> {
     let stones = parse_input(input)?;
     let steps = 75;
 
-    let mut cache: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut memo = Memo::new();
     let mut count = 0;
 
     for stone in stones {
-        let stone_count = get_stone_count_after_ticks(stone, steps, &mut cache);
+        let stone_count = get_stone_count_after_ticks(stone, steps, &mut memo);
         count += stone_count;
     }
 
@@ -143,26 +131,26 @@ mod tests {
 
     #[test]
     fn test_get_stone_count_after_ticks() {
-        let mut cache = HashMap::new();
+        let mut memo = Memo::new();
 
         // 0 will just be 1 after one tick
-        cache.clear();
-        assert_eq!(get_stone_count_after_ticks(0, 1, &mut cache), 1);
+        memo.clear();
+        assert_eq!(get_stone_count_after_ticks(0, 1, &mut memo), 1);
 
         // 1 will become 2024 after one tick
-        cache.clear();
-        assert_eq!(get_stone_count_after_ticks(1, 1, &mut cache), 1);
+        memo.clear();
+        assert_eq!(get_stone_count_after_ticks(1, 1, &mut memo), 1);
 
         // 2024 will become 20 and 24 after one tick
-        cache.clear();
-        assert_eq!(get_stone_count_after_ticks(2024, 1, &mut cache), 2);
+        memo.clear();
+        assert_eq!(get_stone_count_after_ticks(2024, 1, &mut memo), 2);
 
         // 0 should become 4 stones after 5 ticks
-        cache.clear();
-        assert_eq!(get_stone_count_after_ticks(0, 5, &mut cache), 4);
+        memo.clear();
+        assert_eq!(get_stone_count_after_ticks(0, 5, &mut memo), 4);
 
         // 0 should become 7 stones after 6 ticks
-        cache.clear();
-        assert_eq!(get_stone_count_after_ticks(0, 6, &mut cache), 7);
+        memo.clear();
+        assert_eq!(get_stone_count_after_ticks(0, 6, &mut memo), 7);
     }
 }
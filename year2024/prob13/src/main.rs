@@ -1,3 +1,4 @@
+use advent::math::linear::LinearSystem;
 use advent::prelude::*;
 use parse::parse_input;
 
@@ -42,97 +43,52 @@ fn main() -> Result<()> {
 
 /// Best name function so far this year
 ///
-/// Here we solve the two linear equations (I think that's what they're called?), with the
-/// assumption that they overlap in one place and they only use whole numbers.
+/// Here we solve the two linear equations, one for X and one for Y, only accepting whole numbers
+/// of presses.
 ///
 /// The return value is a tuple of how many times A needs to be pressed and how many times B needs
-/// to be pressed (the A and B to solve the equations)
+/// to be pressed (the A and B to solve the equations). If the buttons happen to move the claw in
+/// the same direction there could be more than one way, so we pick the cheapest. That's worked out
+/// directly rather than by trying every way, as part 2 has far too many of them.
 ///
 /// Button A: X+94, Y+34
 /// Button B: X+22, Y+67
 /// Prize: X=8400, Y=5400
-fn solve_problem(problem: Problem) -> Option<(usize, usize)> {
-    // Equation 1 is Ax + Bx = PrizeX, represented by a tuple
-    // Equation 2 is Ay + By = PrizeY
-    let mut eq1 = (
-        problem.a.x as i64,
-        problem.b.x as i64,
-        problem.prize.0 as i64,
-    );
-    let mut eq2 = (
-        problem.a.y as i64,
-        problem.b.y as i64,
-        problem.prize.1 as i64,
-    );
-
-    let ay = eq2.0;
-    let ax = eq1.0;
-    // Multiply equation 1 by Ay and equation 2 by Ax
-    eq1 = (eq1.0 * ay, eq1.1 * ay, eq1.2 * ay);
-    eq2 = (eq2.0 * ax, eq2.1 * ax, eq2.2 * ax);
-
-    if eq1.0 != eq2.0 {
-        panic!("Equations are not compatible");
-    }
-    // Now we have matching terms, so we subtract eq1 from eq2 (or swapped if eq1 is bigger)
-    let eq = if eq1.1 > eq2.1 {
-        (0, eq1.1 - eq2.1, eq1.2 - eq2.2)
-    } else {
-        (0, eq2.1 - eq1.1, eq2.2 - eq1.2)
-    };
-
-    // Solve B
-    // If we can't divide without remainders, we don't have a solution
-    if eq.2 % eq.1 != 0 {
-        return None;
-    }
-    let b = eq.2 / eq.1;
-
-    // Then insert B into original equation 1
-    let eq1 = (
-        problem.a.x as i64,
-        problem.b.x as i64 * b,
-        problem.prize.0 as i64,
-    );
-    // Similarly, if we can't divide without remaindes, we don't have a solution
-    if (eq1.2 - eq1.1) % eq1.0 != 0 {
-        return None;
-    }
-    let a = (eq1.2 - eq1.1) / eq1.0;
-
-    if a < 0 || b < 0 {
-        panic!("Equations are not compatible");
-    }
+fn solve_problem(problem: Problem) -> Result<Option<(usize, usize)>> {
+    let system = LinearSystem::new(
+        vec![
+            vec![problem.a.x, problem.b.x],
+            vec![problem.a.y, problem.b.y],
+        ],
+        vec![problem.prize.0, problem.prize.1],
+    )?;
+
+    system
+        .cheapest_non_negative_integer_solution(&[3, 1])?
+        .map(|presses| Ok((usize::try_from(presses[0])?, usize::try_from(presses[1])?)))
+        .transpose()
+}
 
-    Some((a as usize, b as usize))
+fn total_tokens(problems: impl Iterator<Item = Problem>) -> Result<usize> {
+    problems
+        .map(|problem| Ok(solve_problem(problem)?.map_or(0, |(a, b)| (a * 3) + b)))
+        .sum()
 }
 
 fn part1(input: &str) -> Result<usize> {
     let problems = parse_input(input)?;
 
-    Ok(problems.iter().fold(0, |acc, problem| {
-        if let Some((a, b)) = solve_problem(*problem) {
-            acc + (a * 3) + b
-        } else {
-            acc
-        }
-    }))
+    total_tokens(problems.into_iter())
 }
 
 fn part2(input: &str) -> Result<usize> {
     let problems = parse_input(input)?;
     let extra: u64 = 10_000_000_000_000;
 
-    Ok(problems.iter().fold(0, |acc, problem| {
-        if let Some((a, b)) = solve_problem(Problem {
-            a: problem.a,
-            b: problem.b,
-            prize: (problem.prize.0 + extra, problem.prize.1 + extra),
-        }) {
-            acc + (a * 3) + b
-        } else {
-            acc
-        }
+    total_tokens(problems.into_iter().map(|problem| Problem {
+        a: problem.a,
+        b: problem.b,
+        prize: (problem.prize.0 + extra, problem.prize.1 + extra),
     }))
 }
 
@@ -160,8 +116,22 @@ mod tests {
             prize: (8400, 5400),
         };
 
-        let solution = solve_problem(problem);
+        let solution = solve_problem(problem).unwrap();
 
         assert_eq!(solution, Some((80, 40)));
     }
+
+    #[test]
+    fn test_solve_problem_same_direction() {
+        // A goes four times as far as B for only three times the tokens, so press it the most
+        let problem = Problem {
+            a: Button { x: 8, y: 12 },
+            b: Button { x: 2, y: 3 },
+            prize: (10_000_000_000_002, 15_000_000_000_003),
+        };
+
+        let solution = solve_problem(problem).unwrap();
+
+        assert_eq!(solution, Some((1_250_000_000_000, 1)));
+    }
 }
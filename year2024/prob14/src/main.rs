@@ -1,3 +1,4 @@
+use advent::math::number_theory::lcm;
 use advent::prelude::*;
 use parse::parse_input;
 
@@ -81,30 +82,26 @@ fn get_iterations_for_unique_pos(
     width: i32,
     height: i32,
     states: &[(GridCoordinate<i32>, GridCoordinate<i32>)],
-) -> usize {
-    let mut iterations = 0;
-    let mut states = states.to_vec();
-
-    loop {
-        iterations += 1;
-        states = states
-            .iter()
-            .map(|(pos, vector)| {
-                (
-                    get_pos_after_iterations(width, height, 1, *pos, *vector),
-                    *vector,
-                )
-            })
-            .collect();
-        // Check if all positions are unique?
-        let unique =
-            HashSet::<GridCoordinate<i32>>::from_iter(states.iter().map(|(pos, _)| *pos)).len();
-        if unique == states.len() {
-            break;
+) -> Result<usize> {
+    // Every robot is back where it started after a multiple of both the width and the height, so
+    // if there's no iteration with every robot in a different position before then, there never
+    // will be
+    let period = lcm(width, height).ok_or(error!("The robots take too long to loop around"))?;
+
+    let mut positions = HashSet::with_capacity(states.len());
+    for iterations in 1..=period {
+        positions.clear();
+        let unique = states.iter().all(|(pos, vector)| {
+            positions.insert(get_pos_after_iterations(
+                width, height, iterations, *pos, *vector,
+            ))
+        });
+        if unique {
+            return Ok(usize::try_from(iterations)?);
         }
     }
 
-    iterations
+    Err(error!("The robots never all end up in different positions"))
 }
 
 fn part1(input: &str) -> Result<usize> {
@@ -114,7 +111,7 @@ fn part1(input: &str) -> Result<usize> {
 
 fn part2(input: &str) -> Result<usize> {
     let states = parse_input(input)?;
-    Ok(get_iterations_for_unique_pos(101, 103, &states))
+    get_iterations_for_unique_pos(101, 103, &states)
 }
 
 #[cfg(test)]
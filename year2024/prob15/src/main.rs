@@ -130,13 +130,7 @@ impl Grid {
             return Err(error!("End of instructions"));
         }
 
-        let vector: (i32, i32) = match self.instructions[self.idx] {
-            GridDirection::Up => (-1, 0),
-            GridDirection::Down => (1, 0),
-            GridDirection::Left => (0, -1),
-            GridDirection::Right => (0, 1),
-            _ => unreachable!(),
-        };
+        let vector = self.instructions[self.idx].as_vector();
 
         if self.move_node(self.robot, vector, true)? {
             self.robot += vector;
@@ -150,7 +144,7 @@ impl Grid {
     fn move_node(
         &mut self,
         coordinate: GridCoordinate<i32>,
-        vector: (i32, i32),
+        vector: GridDelta,
         apply_move: bool,
     ) -> Result<bool> {
         let current_node = *coordinate.get(&self.nodes).ok_or(error!("Out of bounds"))?;
@@ -185,7 +179,7 @@ impl Grid {
                     Ok(false)
                 }
             }
-            Some(Node::BoxLeft | Node::BoxRight) if vector.0 == 0 => {
+            Some(Node::BoxLeft | Node::BoxRight) if vector.row == 0 => {
                 // We're moving a wide box horizontally, which is just like moving any other box
                 // really. The first part of the box hit will move the other half of the box, which
                 // will move if there's free space.. so we just continue like normal narrow boxes
@@ -201,7 +195,7 @@ impl Grid {
                     Ok(false)
                 }
             }
-            Some(node @ (Node::BoxLeft | Node::BoxRight)) if vector.1 == 0 => {
+            Some(node @ (Node::BoxLeft | Node::BoxRight)) if vector.column == 0 => {
                 // Now we're cooking.. moving vertically, that's going to require some
                 // backtracking! We'll achieve that with this this 'apply_move' flag, so that we
                 // can see if *all* touched boxes would move, only then will we apply the move..
@@ -211,8 +205,8 @@ impl Grid {
 
                 // We also have to keep in mind that we're covering _two_ nodes at a time here
                 let box_vector = match node {
-                    Node::BoxLeft => (0, 1),
-                    Node::BoxRight => (0, -1),
+                    Node::BoxLeft => GridDelta::new(0, 1),
+                    Node::BoxRight => GridDelta::new(0, -1),
                     _ => unreachable!(),
                 };
                 let next_pos_other = next_pos + box_vector;
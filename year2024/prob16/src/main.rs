@@ -1,11 +1,12 @@
 use advent::prelude::*;
+use advent::search::{dijkstra_all_paths, SearchResult};
 use parse::parse_input;
 use std::fmt;
 
 mod parse;
 
-const MOVE_COST: i32 = 1;
-const TURN_COST: i32 = 1000;
+const MOVE_COST: u32 = 1;
+const TURN_COST: u32 = 1000;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Node {
@@ -29,7 +30,7 @@ impl fmt::Display for Node {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Map {
-    nodes: Vec<Vec<Node>>,
+    nodes: Grid<Node>,
     start: GridCoordinate<i32>,
     end: GridCoordinate<i32>,
     direction: CompassDirection,
@@ -37,116 +38,61 @@ struct Map {
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in self.nodes.iter() {
-            for node in row.iter() {
-                write!(f, "{}", node)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.nodes)
     }
 }
 
 impl Map {
-    fn new(nodes: Vec<Vec<Node>>) -> Self {
-        // NOTE: Is this too much? It'll also iterate over all nodes, even when start + end have
-        // been found.. but it's fine for AoC
-        let (start, end) = nodes.iter().enumerate().fold(
-            (GridCoordinate::new(0, 0), GridCoordinate::new(0, 0)),
-            |(start, end), (y, row)| {
-                row.iter()
-                    .enumerate()
-                    .fold((start, end), |(start, end), (x, node)| match node {
-                        Node::Start => (GridCoordinate::new(y as i32, x as i32), end),
-                        Node::End => (start, GridCoordinate::new(y as i32, x as i32)),
-                        _ => (start, end),
-                    })
-            },
-        );
-        Self {
+    fn new(
+        nodes: Grid<Node>,
+        start: GridCoordinate<usize>,
+        end: GridCoordinate<usize>,
+    ) -> Result<Self> {
+        let start = start
+            .cast()
+            .ok_or(error!("Start is too far into the maze"))?;
+        let end = end.cast().ok_or(error!("End is too far into the maze"))?;
+
+        Ok(Self {
             nodes,
             start,
             end,
             direction: CompassDirection::East,
-        }
+        })
     }
 }
 
-type State = (
-    i32,                      // Score (negative)
-    GridCoordinate<i32>,      // Current position
-    CompassDirection,         // Current direction
-    Vec<GridCoordinate<i32>>, // Path taken to get here
-);
-
-fn solve_maze(
-    map: &Map,
-    explore_duplicate_best: bool,
-) -> Result<(u32, HashSet<GridCoordinate<i32>>)> {
-    // Let's do a BFS with a queue, trying lowest scores first
-    // Since BinaryHeap is a max-heap, we'll store the score as negative numbers!
-    let mut queue: BinaryHeap<State> =
-        BinaryHeap::from(vec![(0, map.start, map.direction, vec![map.start])]);
-    // We'll keep track of seen nodes _with_ the direction, as turning around is expensive, but is
-    // a 'different state' for us to try
-    let mut seen_nodes: HashMap<GridCoordinate<i32>, i32> = HashMap::new();
-    let mut min_score = None;
-
-    let mut optimal_path_nodes = HashSet::new();
-
-    while let Some((score, coord, direction, path)) = queue.pop() {
-        if coord == map.end {
-            let score = score.unsigned_abs();
-            optimal_path_nodes.extend(path);
-            if let Some(min) = min_score {
-                if score < min {
-                    // We shouldn't be able to get here! Since we're doing BFS, first time we get
-                    // to the end, is the fastest
-                    unreachable!();
-                }
-            } else {
-                min_score = Some(score);
+type State = (GridCoordinate<i32>, CompassDirection);
+
+fn solve_maze(map: &Map) -> SearchResult<State, u32> {
+    // Turning around is expensive, so the direction we're facing is part of the state, and
+    // recording all the optimal paths lets part 2 find every tile on any of them
+    dijkstra_all_paths(
+        [(map.start, map.direction)],
+        |&(coord, direction): &State| {
+            let mut next_states = Vec::with_capacity(3);
+
+            // If there's not a wall in front of us, let's queue that up
+            let forward = coord + direction.as_vector();
+            if forward
+                .get(&map.nodes)
+                .is_some_and(|node| *node != Node::Wall)
+            {
+                next_states.push(((forward, direction), MOVE_COST));
             }
-            continue;
-        }
 
-        // If we already have min_score and have a higher current score, we prune
-        if let Some(min_score) = min_score {
-            if score.unsigned_abs() > min_score {
-                continue;
-            }
-        }
-
-        // If there's not a wall in front of us, let's queue that up
-        let forward = coord + direction.as_vector();
-        if !seen_nodes.contains_key(&forward)
-            || (explore_duplicate_best && seen_nodes.get(&forward) >= Some(&score))
-        {
-            seen_nodes.insert(forward, score);
-            if let Some(node) = forward.get(&map.nodes) {
-                if *node != Node::Wall {
-                    let new_path = path.iter().cloned().chain(Some(forward)).collect();
-                    queue.push((score - MOVE_COST, forward, direction, new_path));
+            // Let's also queue up turning left or right, if there's an opening
+            for turned in [direction.left_90(), direction.right_90()] {
+                let side = coord + turned.as_vector();
+                if side.get(&map.nodes).is_some_and(|node| *node != Node::Wall) {
+                    next_states.push(((coord, turned), TURN_COST));
                 }
             }
-        }
-        // Let's also queue up turning left or right, if there's an opening
-        let left = coord + direction.left_90().as_vector();
-        if let Some(node) = left.get(&map.nodes) {
-            if *node != Node::Wall && !seen_nodes.contains_key(&left) {
-                queue.push((score - TURN_COST, coord, direction.left_90(), path.clone()));
-            }
-        }
 
-        let right = coord + direction.right_90().as_vector();
-        if let Some(node) = right.get(&map.nodes) {
-            if *node != Node::Wall && !seen_nodes.contains_key(&right) {
-                queue.push((score - TURN_COST, coord, direction.right_90(), path.clone()));
-            }
-        }
-    }
-
-    Ok((min_score.unwrap(), optimal_path_nodes))
+            next_states
+        },
+        |(coord, _)| *coord == map.end,
+    )
 }
 
 fn main() -> Result<()> {
@@ -176,17 +122,21 @@ fn main() -> Result<()> {
 fn part1(input: &str) -> Result<u32> {
     let map = parse_input(input)?;
 
-    let (result, _) = solve_maze(&map, false)?;
-
-    Ok(result)
+    solve_maze(&map)
+        .cost()
+        .ok_or(error!("No path through the maze"))
 }
 
 fn part2(input: &str) -> Result<usize> {
     let map = parse_input(input)?;
 
-    let (_, optimal_path) = solve_maze(&map, true)?;
+    let optimal_tiles: HashSet<GridCoordinate<i32>> = solve_maze(&map)
+        .optimal_path_states()
+        .into_iter()
+        .map(|(coord, _)| coord)
+        .collect();
 
-    Ok(optimal_path.len())
+    Ok(optimal_tiles.len())
 }
 
 #[cfg(test)]
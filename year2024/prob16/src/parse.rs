@@ -1,29 +1,25 @@
 use crate::{Map, Node};
+use advent::parsers::nom_grid_with_markers;
 use advent::prelude::*;
 
-fn nom_node(input: &str) -> IResult<&str, Node> {
-    alt((
-        value(Node::Wall, char('#')),
-        value(Node::Floor, char('.')),
-        value(Node::Start, char('S')),
-        value(Node::End, char('E')),
-    ))
-    .parse(input)
-}
-
-fn nom_row(input: &str) -> IResult<&str, Vec<Node>> {
-    many1(nom_node).parse(input)
-}
-
-fn nom_map(input: &str) -> IResult<&str, Vec<Vec<Node>>> {
-    separated_list1(newline, nom_row).parse(input)
+impl TryFrom<char> for Node {
+    type Error = AdventError;
+
+    fn try_from(c: char) -> Result<Self> {
+        match c {
+            '#' => Ok(Node::Wall),
+            '.' => Ok(Node::Floor),
+            'S' => Ok(Node::Start),
+            'E' => Ok(Node::End),
+            _ => Err(AdventError::InvalidInput),
+        }
+    }
 }
 
 pub fn parse_input(input: &str) -> Result<Map> {
-    let (_, nodes) = nom_map(input)
-        .map_err(|e| AdventError::ParseError(format!("Failed to parse input: {:?}", e)))?;
+    let (_, (nodes, markers)) = nom_grid_with_markers(&['S', 'E']).parse(input)?;
 
-    Ok(Map::new(nodes))
+    Map::new(nodes, markers.require('S')?, markers.require('E')?)
 }
 
 #[cfg(test)]
@@ -31,42 +27,27 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_nom_node() {
-        assert_eq!(nom_node("#.S"), Ok((".S", Node::Wall)));
-        assert_eq!(nom_node(".#S"), Ok(("#S", Node::Floor)));
-        assert_eq!(nom_node("S.#"), Ok((".#", Node::Start)));
-    }
-
-    #[test]
-    fn test_nom_row() {
-        assert_eq!(
-            nom_row("#.S"),
-            Ok(("", vec![Node::Wall, Node::Floor, Node::Start]))
-        );
-    }
-
-    #[test]
-    fn test_nom_map() {
-        assert_eq!(
-            nom_map("#.S\n.#E"),
-            Ok((
-                "",
-                vec![
-                    vec![Node::Wall, Node::Floor, Node::Start],
-                    vec![Node::Floor, Node::Wall, Node::End]
-                ]
-            ))
-        );
+    fn test_node_try_from() {
+        assert_eq!(Node::try_from('#').unwrap(), Node::Wall);
+        assert_eq!(Node::try_from('.').unwrap(), Node::Floor);
+        assert_eq!(Node::try_from('S').unwrap(), Node::Start);
+        assert_eq!(Node::try_from('E').unwrap(), Node::End);
+        assert!(Node::try_from('x').is_err());
     }
 
     #[test]
     fn test_parse_input() {
+        let map = parse_input("#.S\n.#E").unwrap();
+
+        assert_eq!(map.start, GridCoordinate::new(0, 2));
+        assert_eq!(map.end, GridCoordinate::new(1, 2));
         assert_eq!(
-            parse_input("#.S\n.#E").unwrap(),
-            Map::new(vec![
+            map.nodes,
+            Grid::from_rows(vec![
                 vec![Node::Wall, Node::Floor, Node::Start],
                 vec![Node::Floor, Node::Wall, Node::End]
             ])
+            .unwrap()
         );
     }
 }
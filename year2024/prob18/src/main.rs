@@ -1,4 +1,5 @@
 use advent::prelude::*;
+use advent::search::bfs;
 use parse::parse_input;
 use std::{env, fmt, fs};
 
@@ -77,46 +78,25 @@ impl Grid {
     }
 
     fn get_shortest_distance(
-        &mut self,
+        &self,
         from: GridCoordinate<i32>,
         to: GridCoordinate<i32>,
     ) -> Result<u32> {
-        // Time to solve a maze again!
-        // The queue is a binary heap of tuples, first element is the distance so far (negative),
-        // followed by the manhattan distance between the nodes, and then followed by the
-        // coordinate. We'll be doing BFS, so we don't need to keep track of the path itself...
-        // though we haven't seen part 2 yet.
-        let mut queue: BinaryHeap<(i32, GridCoordinate<i32>)> = BinaryHeap::new();
-        let mut seen_nodes: HashSet<GridCoordinate<i32>> = HashSet::new();
-
-        queue.push((0, from));
-
-        let mut dist_so_far = 0;
-
-        while let Some((dist, coord)) = queue.pop() {
-            if coord == to {
-                return Ok(dist.unsigned_abs());
-            }
-            if seen_nodes.contains(&coord) {
-                continue;
-            }
-            seen_nodes.insert(coord);
-
-            if dist < dist_so_far {
-                dist_so_far = dist;
-            }
+        // Time to solve a maze again! Every step costs the same, so a plain BFS will do
+        let result = bfs(
+            [from],
+            |coord| {
+                coord
+                    .edge_coordinates(1)
+                    .filter(|neighbour| self.get_node(neighbour) == Some(0))
+                    .collect::<Vec<_>>()
+            },
+            |coord| *coord == to,
+        );
 
-            // Check all the neighbours, if they're empty and we haven't been there, let's go there
-            for neighbour in coord.edge_coordinates(1) {
-                if let Some(node) = self.get_node(&neighbour) {
-                    if !seen_nodes.contains(&neighbour) && node == 0 {
-                        queue.push((dist - 1, neighbour));
-                    }
-                }
-            }
-        }
+        let distance = result.cost().ok_or(error!("No path found!"))?;
 
-        Err(error!("No path found!"))
+        Ok(u32::try_from(distance)?)
     }
 }
 
@@ -1,4 +1,5 @@
 use advent::prelude::*;
+use advent::trie::Trie;
 use parse::parse_input;
 use std::env;
 use std::fs;
@@ -16,83 +17,6 @@ enum Colour {
     Green,
 }
 
-impl Colour {
-    fn to_char(&self) -> char {
-        match self {
-            Colour::White => 'w',
-            Colour::Blue => 'u',
-            Colour::Black => 'b',
-            Colour::Red => 'r',
-            Colour::Green => 'g',
-        }
-    }
-}
-
-#[derive(Debug)]
-struct PatternTrie {
-    children: HashMap<Colour, Box<PatternTrie>>,
-    end: bool,
-}
-
-impl PatternTrie {
-    fn new() -> Self {
-        Self {
-            children: HashMap::new(),
-            end: false,
-        }
-    }
-
-    fn insert(&mut self, pattern: &[Colour]) {
-        let mut current = self;
-
-        for colour in pattern {
-            current = current
-                .children
-                .entry(colour.clone())
-                .or_insert(Box::new(Self::new()));
-        }
-
-        current.end = true;
-    }
-
-    /// How many ways can a pattern be constructed?
-    fn options_count(&self, pattern: &[Colour], cache: &mut HashMap<String, usize>) -> usize {
-        // Check if we've seen this pattern before
-        let key = pattern.iter().map(Colour::to_char).collect::<String>();
-        if let Some(count) = cache.get(&key) {
-            return *count;
-        }
-
-        let mut count = 0;
-        let mut options = vec![self];
-        for (idx, colour) in pattern.iter().enumerate() {
-            options = options
-                .iter()
-                .flat_map(|trie| {
-                    trie.children
-                        .get(colour)
-                        .map(|child| vec![child.as_ref()])
-                        .unwrap_or_default()
-                })
-                .collect();
-
-            // If we reach the end, we'll have to check how many ways we can construct the rest..
-            // we'll just recurse here
-            // I think we'll need to multiply by the number of ways we got here.. right? Because
-            // that's all options to _get here_
-            let ends_count = options.iter().filter(|trie| trie.end).count();
-            let sub_pattern = &pattern[idx + 1..];
-            if !sub_pattern.is_empty() {
-                count += self.options_count(sub_pattern, cache) * ends_count;
-            }
-        }
-
-        let total_count = count + options.iter().filter(|trie| trie.end).count();
-        cache.insert(key, total_count);
-        total_count
-    }
-}
-
 fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
     let input = if args.len() > 1 {
@@ -127,33 +51,23 @@ fn main() -> Result<()> {
 fn part1(input: &str) -> Result<usize> {
     let (towels, patterns) = parse_input(input)?;
 
-    let mut towel_trie = PatternTrie::new();
-    for towel in towels {
-        towel_trie.insert(&towel);
-    }
-    let mut cache: HashMap<String, usize> = HashMap::new();
-
-    Ok(patterns.iter().fold(0, |acc, pattern| {
-        if towel_trie.options_count(pattern, &mut cache) > 0 {
-            acc + 1
-        } else {
-            acc
-        }
-    }))
+    let towel_trie: Trie<Colour> = towels.into_iter().collect();
+
+    Ok(patterns
+        .iter()
+        .filter(|pattern| towel_trie.can_tile(pattern))
+        .count())
 }
 
 fn part2(input: &str) -> Result<usize> {
     let (towels, patterns) = parse_input(input)?;
 
-    let mut towel_trie = PatternTrie::new();
-    for towel in towels {
-        towel_trie.insert(&towel);
-    }
-    let mut cache: HashMap<String, usize> = HashMap::new();
+    let towel_trie: Trie<Colour> = towels.into_iter().collect();
 
-    Ok(patterns.iter().fold(0, |acc, pattern| {
-        acc + towel_trie.options_count(pattern, &mut cache)
-    }))
+    Ok(patterns
+        .iter()
+        .map(|pattern| towel_trie.count_tilings(pattern))
+        .sum())
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use advent::graph::Graph;
 use advent::prelude::*;
 use parse::parse_input;
 use std::env;
@@ -97,39 +98,10 @@ fn part2(input: &str) -> Result<String> {
 
     // This time we're searching for the largest clique (just learned that term) in this graph
     //
-    // Based on the Wiki article about the 'clique problem', a simple greedy algo should be fine to
-    // find a single maximal clique.. so let's try that
-
-    let mut adjacency = HashMap::new();
-    for (from, to) in &connections {
-        adjacency
-            .entry(from)
-            .or_insert_with(HashSet::new)
-            .insert(to);
-        adjacency
-            .entry(to)
-            .or_insert_with(HashSet::new)
-            .insert(from);
-    }
-
-    let mut largest_clique: Vec<&Computer> = Vec::new();
-
-    for (&node, neighbours) in adjacency.iter() {
-        let mut candidate_clique: Vec<&Computer> = vec![node];
-
-        for &neighbour in neighbours {
-            if candidate_clique
-                .iter()
-                .all(|&n| adjacency[n].contains(neighbour))
-            {
-                candidate_clique.push(neighbour);
-            }
-        }
-
-        if candidate_clique.len() > largest_clique.len() {
-            largest_clique = candidate_clique.clone();
-        }
-    }
+    // A greedy pass only finds _a_ clique that can't grow any more, which isn't always the biggest
+    // one, so we do the full Bron–Kerbosch search instead
+    let network = Graph::undirected_from_edges(connections);
+    let mut largest_clique = network.maximum_clique();
 
     largest_clique.sort_unstable();
 
@@ -1,8 +1,8 @@
+use advent::intervals::IntervalSet;
 use advent::prelude::*;
 use parse::parse_input;
 use std::env;
 use std::fs;
-use std::ops::RangeInclusive;
 
 mod parse;
 
@@ -40,70 +40,21 @@ fn main() -> Result<()> {
 fn part1(input: &str) -> Result<usize> {
     let (fresh_ranges, ingredient_ids) = parse_input(input)?;
 
-    let joined_ranges = prepare_ranges(fresh_ranges)?;
-
-    // This should be easy with just a naive check.. I'm curious what part 2 will be though.
-    // Definitely will have to join ranges that overlap for example
-    let count = ingredient_ids.iter().fold(0, |acc, ingredient_id| {
-        acc + if joined_ranges
-            .iter()
-            .any(|range| range.contains(ingredient_id))
-        {
-            1
-        } else {
-            0
-        }
-    });
-
-    Ok(count)
-}
-
-fn is_overlapping<T: Copy + Ord>(a: &RangeInclusive<T>, b: &RangeInclusive<T>) -> bool {
-    a.start() <= b.end() && b.start() <= a.end()
-}
-
-/// Join two ranges
-///
-/// Note: There are no checks that they overlap!
-fn join_ranges<T: Copy + Ord>(a: &RangeInclusive<T>, b: &RangeInclusive<T>) -> RangeInclusive<T> {
-    RangeInclusive::new(*a.start().min(b.start()), *a.end().max(b.end()))
-}
-
-fn prepare_ranges<T: Copy + Ord>(ranges: Vec<RangeInclusive<T>>) -> Result<Vec<RangeInclusive<T>>> {
-    let mut ranges = ranges;
-
-    // Let's sort by the start
-    ranges.sort_by(|a, b| a.start().cmp(b.start()));
-
-    // Combine all overlapping ranges, then just sum up?
-    let mut joined_ranges = Vec::new();
-
-    let mut current_range = ranges.first().unwrap().clone();
+    let fresh: IntervalSet<u64> = fresh_ranges.into_iter().collect();
 
-    for fresh_range in ranges.iter().skip(1) {
-        if is_overlapping(&current_range, fresh_range) {
-            current_range = join_ranges(&current_range, fresh_range);
-        } else {
-            joined_ranges.push(current_range);
-            current_range = fresh_range.clone();
-        }
-    }
-
-    joined_ranges.push(current_range);
-
-    Ok(joined_ranges)
+    Ok(ingredient_ids
+        .iter()
+        .filter(|ingredient_id| fresh.contains(**ingredient_id))
+        .count())
 }
 
 fn part2(input: &str) -> Result<u64> {
     let (fresh_ranges, _) = parse_input(input)?;
 
-    let joined_ranges = prepare_ranges(fresh_ranges)?;
+    // Overlapping ranges are joined up in the set, so we can just count what's covered
+    let fresh: IntervalSet<u64> = fresh_ranges.into_iter().collect();
 
-    let count = joined_ranges
-        .iter()
-        .fold(0, |acc, range| acc + (range.end() - range.start() + 1));
-
-    Ok(count)
+    Ok(fresh.total_length())
 }
 
 #[cfg(test)]
@@ -123,22 +74,11 @@ mod tests {
     }
 
     #[test]
-    fn test_is_overlapping() {
-        assert!(is_overlapping(&(1..=5), &(5..=10)));
-        assert!(is_overlapping(&(1..=6), &(5..=10)));
-        assert!(is_overlapping(&(4..=6), &(1..=10)));
-        assert!(is_overlapping(&(4..=6), &(1..=4)));
-        assert!(is_overlapping(&(4..=6), &(1..=5)));
-
-        assert!(!is_overlapping(&(1..=3), &(4..=5)));
-        assert!(!is_overlapping(&(4..=5), &(1..=3)));
-    }
+    fn test_overlapping_ranges_are_joined() {
+        let fresh: IntervalSet<u64> = [1..=5, 5..=10, 12..=14, 13..=20].into_iter().collect();
 
-    #[test]
-    fn test_join_ranges() {
-        assert_eq!(join_ranges(&(1..=5), &(5..=10)), 1..=10);
-        assert_eq!(join_ranges(&(1..=8), &(5..=10)), 1..=10);
-        assert_eq!(join_ranges(&(1..=8), &(1..=10)), 1..=10);
-        assert_eq!(join_ranges(&(4..=8), &(1..=10)), 1..=10);
+        assert_eq!(fresh.iter().collect::<Vec<_>>(), vec![1..=10, 12..=20]);
+        assert!(fresh.contains(5));
+        assert!(!fresh.contains(11));
     }
 }
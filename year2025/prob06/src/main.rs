@@ -20,6 +20,13 @@ enum Problem {
 }
 
 impl Problem {
+    fn new(symbol: &Symbol, values: Vec<u64>) -> Self {
+        match symbol {
+            Symbol::Plus => Self::Plus(values),
+            Symbol::Multiply => Self::Multiply(values),
+        }
+    }
+
     fn do_math(&self) -> u64 {
         match self {
             Self::Plus(values) => values.iter().sum(),
@@ -59,20 +66,20 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn rotate(number_rows: Vec<Vec<u64>>, symbols_row: Vec<Symbol>) -> Vec<Problem> {
-    symbols_row
+fn rotate(number_rows: Vec<Vec<u64>>, symbols_row: Vec<Symbol>) -> Result<Vec<Problem>> {
+    let mut numbers = Grid::from_rows(number_rows)?;
+    numbers.transpose();
+
+    Ok(symbols_row
         .iter()
-        .enumerate()
-        .map(|(idx, symbol)| match &symbol {
-            Symbol::Plus => Problem::Plus(number_rows.iter().map(|row| row[idx]).collect()),
-            Symbol::Multiply => Problem::Multiply(number_rows.iter().map(|row| row[idx]).collect()),
-        })
-        .collect()
+        .zip(numbers.rows())
+        .map(|(symbol, values)| Problem::new(symbol, values.to_vec()))
+        .collect())
 }
 
 fn part1(input: &str) -> Result<u64> {
     let (number_rows, symbols_row) = parse_input_part1(input)?;
-    let out = rotate(number_rows, symbols_row)
+    let out = rotate(number_rows, symbols_row)?
         .iter()
         .map(|problem| problem.do_math())
         .sum();
@@ -80,53 +87,11 @@ fn part1(input: &str) -> Result<u64> {
 }
 
 fn part2(input: &str) -> Result<u64> {
-    let chars = parse_input_part2(input)?;
-    let rows = chars.len();
-    let cols = chars[0].len();
-
-    let transposed = (0..cols)
-        .map(|c| (0..rows).map(|r| chars[r][c]).collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut output = 0;
-
-    let mut symbol: Option<Symbol> = None;
-    let mut current_value = 0;
-
-    for row in transposed.into_iter() {
-        if row.iter().all(|r| r == &' ') {
-            // Reset if whitespace line
-            symbol = None;
-            output += current_value;
-        } else {
-            if symbol.is_none() {
-                // Starting new math
-                match row[row.len() - 1] {
-                    '+' => {
-                        symbol = Some(Symbol::Plus);
-                        current_value = 0;
-                    }
-                    '*' => {
-                        symbol = Some(Symbol::Multiply);
-                        current_value = 1;
-                    }
-                    _ => panic!("Uh oh"),
-                }
-            }
-            let row_value = row
-                .iter()
-                .filter(|c| c.is_ascii_digit())
-                .collect::<String>()
-                .parse::<u64>()?;
-            match symbol {
-                Some(Symbol::Plus) => current_value += row_value,
-                Some(Symbol::Multiply) => current_value *= row_value,
-                None => panic!("Uh oh 2"),
-            }
-        }
-    }
-
-    Ok(output + current_value)
+    let out = parse_input_part2(input)?
+        .into_iter()
+        .map(|(symbol, values)| Problem::new(&symbol, values).do_math())
+        .sum();
+    Ok(out)
 }
 
 #[cfg(test)]
@@ -150,7 +115,7 @@ mod tests {
         let number_rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
         let symbols_row = vec![Symbol::Plus, Symbol::Multiply, Symbol::Multiply];
 
-        let rotated = rotate(number_rows, symbols_row);
+        let rotated = rotate(number_rows, symbols_row).unwrap();
 
         assert_eq!(
             rotated,
@@ -1,5 +1,6 @@
 use crate::Symbol;
-use advent::{parsers::nom_unsigned_digit, prelude::*};
+use advent::parsers::{TextBlock, nom_unsigned_digit};
+use advent::prelude::*;
 
 fn nom_number_row(input: &str) -> IResult<&str, Vec<u64>> {
     delimited(space0, separated_list1(space1, nom_unsigned_digit), space0).parse(input)
@@ -28,13 +29,21 @@ pub fn parse_input_part1(input: &str) -> Result<(Vec<Vec<u64>>, Vec<Symbol>)> {
     Ok((number_rows, symbols_row))
 }
 
-pub fn parse_input_part2(input: &str) -> Result<Vec<Vec<char>>> {
-    // For part 2 we're moving a lot of the actual parsing to the main function.. we need to rotate
-    // the character matrix by 90 degrees counter clockwise, then parse at that point. Whitespaces
-    // matter here!
-    let (_, out) = separated_list1(newline, many1(one_of(" 0123456789+*"))).parse(input)?;
+/// For part 2 each problem is a block of columns between blank ones, with every number written
+/// top to bottom and the symbol on the last line
+pub fn parse_input_part2(input: &str) -> Result<Vec<(Symbol, Vec<u64>)>> {
+    TextBlock::new(input)
+        .split_on_blank_columns()
+        .iter()
+        .map(|problem| {
+            let height = problem.height().saturating_sub(1);
+            let symbol_row = problem.rows(height..).lines().next().unwrap_or_default();
+            let (_, symbol) = preceded(space0, nom_symbol).parse(&symbol_row)?;
+            let numbers = problem.rows(..height).vertical_numbers()?;
 
-    Ok(out)
+            Ok((symbol, numbers))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -92,9 +101,8 @@ mod tests {
         assert_eq!(
             out,
             vec![
-                vec![' ', ' ', '1', ' ', '2', ' '],
-                vec!['1', '2', '3', ' ', '3', '4'],
-                vec!['*', ' ', ' ', ' ', '+', ' '],
+                (Symbol::Multiply, vec![1, 2, 13]),
+                (Symbol::Plus, vec![23, 4])
             ]
         );
     }
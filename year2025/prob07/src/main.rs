@@ -1,3 +1,4 @@
+use advent::automaton::{Automaton, Edges, Neighbourhood};
 use advent::prelude::*;
 use parse::parse_input;
 use std::env;
@@ -29,9 +30,6 @@ type Col = usize;
 type Row = usize;
 type Coord = (Row, Col);
 
-#[derive(PartialEq)]
-struct Quantum(bool);
-
 #[allow(dead_code)]
 struct Grid {
     inner: Vec<Nodes>,
@@ -67,93 +65,46 @@ impl Grid {
         self.memo_grid[row + col * self.height]
     }
 
-    fn get_beam_splits(&mut self, (row, col): Coord, quantum: &Quantum) -> Result<usize> {
-        match (quantum, self.get(row, col)) {
-            (Quantum(false), Some(_)) => {
-                // We return no more splits, we've been here already
-                return Ok(0);
-            }
-            (Quantum(true), Some(splits)) => {
-                // We return the splits that have been observed from here, since we're in a
-                // different universe but just reached it from a different path
-                // TODO: Do I need to .. store the paths?
-                return Ok(splits);
-            }
-            (_, None) => {} // We continue
+    /// The number of timelines a particle going down from this coordinate ends up in
+    fn count_timelines(&mut self, (row, col): Coord) -> Result<usize> {
+        if let Some(timelines) = self.get(row, col) {
+            // We've reached here from a different path already, and the timelines from here on
+            // are the same
+            return Ok(timelines);
         }
 
         let coord_below = (row + 1, col);
 
-        let new_splits = match self.get_node_at_coord(&coord_below) {
-            Some(Node::Empty) => {
-                if *quantum == Quantum(false) {
-                    self.set_node_at_coord(&coord_below, Node::Beam)?;
-                }
-                self.get_beam_splits(coord_below, quantum)?
-            }
+        let timelines = match self.get_node_at_coord(&coord_below) {
+            Some(Node::Empty) => self.count_timelines(coord_below)?,
             Some(Node::Splitter) => {
-                // We split to the sides
+                // We split to the sides, each side being its own set of timelines
                 // NOTE: beam_col - 1 if beam_col is 0 is bad due to usize
-                let left_splits = if col > 0 {
-                    let coord_left = (row + 1, col - 1);
-                    if *quantum == Quantum(false) {
-                        self.set_node_at_coord(&coord_left, Node::Beam)?;
-                    }
-                    self.get_beam_splits(coord_left, quantum)?
+                let left_timelines = if col > 0 {
+                    self.count_timelines((row + 1, col - 1))?
                 } else {
                     0
                 };
-                let right_splits = if col < self.width() - 1 {
-                    let coord_right = (row + 1, col + 1);
-                    if *quantum == Quantum(false) {
-                        self.set_node_at_coord(&coord_right, Node::Beam)?;
-                    }
-                    self.get_beam_splits(coord_right, quantum)?
+                let right_timelines = if col < self.width() - 1 {
+                    self.count_timelines((row + 1, col + 1))?
                 } else {
                     0
                 };
-                match quantum {
-                    Quantum(false) => left_splits + right_splits + 1, // Count number of splits
-                    // plus the current one
-                    Quantum(true) => left_splits + right_splits, // We're counting worlds, so we
-                                                                 // don't count the split that
-                                                                 // happened here
-                }
+                left_timelines + right_timelines
             }
-            Some(Node::Beam) => {
-                match quantum {
-                    Quantum(false) => 0, // Ignore beam already there
-                    Quantum(true) => unimplemented!("Quantum"),
-                }
-            }
-            None => match quantum {
-                Quantum(false) => 0, // No more splits
-                Quantum(true) => 1,  // When we go out of bounds in the quantum world, we count 1
-                                      // for the number of world at this point
-            },
-            _ => unimplemented!(),
+            // When we go out of bounds, we've reached the end of one timeline
+            None => 1,
+            Some(node) => return Err(error!("Unexpected {} in the path of the beam", node)),
         };
 
-        self.set(row, col, new_splits);
+        self.set(row, col, timelines);
 
-        Ok(new_splits)
+        Ok(timelines)
     }
 
     fn get_node_at_coord(&self, (row, col): &Coord) -> Option<&Node> {
         self.inner.get(*row).and_then(|row| row.get(*col))
     }
-
-    fn set_node_at_coord(&mut self, (row, col): &Coord, node: Node) -> Result<()> {
-        let inner_node = self
-            .inner
-            .get_mut(*row)
-            .and_then(|row| row.get_mut(*col))
-            .ok_or_else(|| error!("Coords out of bounds"))?;
-
-        *inner_node = node;
-
-        Ok(())
-    }
 }
 
 impl std::fmt::Display for Grid {
@@ -224,23 +175,53 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Let the beams spread through the manifold until they stop changing, then count the splitters
+/// that have a beam coming into them
 fn part1(input: &str) -> Result<usize> {
     let node_rows = parse_input(input)?;
-    let mut grid = Grid::try_from(node_rows)?;
-
-    let beam_splits = grid.get_beam_splits(grid.start, &Quantum(false))?;
+    let manifold = advent::prelude::Grid::from_rows(node_rows)?;
+    let height = manifold.height();
+
+    // A cell is lit from above, or by a splitter beside it that has a beam coming into it, so
+    // it looks at the cell above, then each side along with the cell above that
+    let neighbourhood = Neighbourhood::Custom(vec![
+        GridDelta::new(-1, 0),
+        GridDelta::new(0, -1),
+        GridDelta::new(-1, -1),
+        GridDelta::new(0, 1),
+        GridDelta::new(-1, 1),
+    ]);
+    let lit = |node: Option<&Node>| matches!(node, Some(Node::Start | Node::Beam));
+
+    let mut automaton = Automaton::new(manifold, neighbourhood, Edges::Bounded);
+    // Beams move down one row each step, so they've gone as far as they can within the height
+    automaton.run_until_stable(height + 1, |node, neighbours| match node {
+        Node::Empty
+            if lit(neighbours.get(0))
+                || (neighbours.get(1) == Some(&Node::Splitter) && lit(neighbours.get(2)))
+                || (neighbours.get(3) == Some(&Node::Splitter) && lit(neighbours.get(4))) =>
+        {
+            Node::Beam
+        }
+        _ => node.clone(),
+    })?;
+
+    let (manifold, _) = automaton.to_grid();
+    let splits = manifold
+        .positions(|node| *node == Node::Splitter)
+        .filter(|coord| {
+            coord.row > 0 && lit(manifold.get(&GridCoordinate::new(coord.row - 1, coord.column)))
+        })
+        .count();
 
-    Ok(beam_splits)
+    Ok(splits)
 }
 
 fn part2(input: &str) -> Result<usize> {
     let node_rows = parse_input(input)?;
     let mut grid = Grid::try_from(node_rows)?;
 
-    let beam_splits = grid.get_beam_splits(grid.start, &Quantum(true))?;
-
-    // Since each split creates a new world, and we start in one, then we +1?
-    Ok(beam_splits)
+    grid.count_timelines(grid.start)
 }
 
 #[cfg(test)]